@@ -0,0 +1,8 @@
+-- Add parent/child hierarchy to tasks
+ALTER TABLE tasks ADD COLUMN parent_id UUID REFERENCES tasks(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_tasks_parent_id ON tasks(parent_id);
+
+-- A task can never be its own parent
+ALTER TABLE tasks ADD CONSTRAINT chk_parent_not_self
+    CHECK (parent_id IS NULL OR parent_id <> id);
@@ -0,0 +1,14 @@
+-- Create task dependencies table ("task_id is blocked by blocked_by_id")
+CREATE TABLE task_dependencies (
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    blocked_by_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (task_id, blocked_by_id)
+);
+
+-- Create index for reverse lookups (tasks blocked by a given task)
+CREATE INDEX idx_task_dependencies_blocked_by_id ON task_dependencies(blocked_by_id);
+
+-- Add constraints
+ALTER TABLE task_dependencies ADD CONSTRAINT chk_dependency_not_self
+    CHECK (task_id <> blocked_by_id);
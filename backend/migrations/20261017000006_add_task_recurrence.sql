@@ -0,0 +1,8 @@
+-- Add recurrence to tasks
+-- recurrence_series_id is the id of the first instance; instances are numbered from 1
+ALTER TABLE tasks ADD COLUMN recurrence_rule TEXT;
+ALTER TABLE tasks ADD COLUMN recurrence_series_id UUID;
+ALTER TABLE tasks ADD COLUMN recurrence_index INTEGER;
+
+-- Each occurrence of a series is generated at most once
+CREATE UNIQUE INDEX idx_tasks_recurrence_instance ON tasks(recurrence_series_id, recurrence_index);
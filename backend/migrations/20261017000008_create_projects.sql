@@ -0,0 +1,32 @@
+-- Create Projects table
+CREATE TABLE projects (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    color VARCHAR(7),
+    description TEXT,
+    archived BOOLEAN NOT NULL DEFAULT FALSE,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Create indexes for performance
+CREATE INDEX idx_projects_user_id ON projects(user_id, sort_order);
+
+-- Project names are unique per user, ignoring case
+CREATE UNIQUE INDEX idx_projects_user_name ON projects(user_id, LOWER(name));
+
+-- Add constraints
+ALTER TABLE projects ADD CONSTRAINT chk_project_name_length
+    CHECK (char_length(name) >= 1 AND char_length(name) <= 100);
+ALTER TABLE projects ADD CONSTRAINT chk_project_color
+    CHECK (color IS NULL OR color ~ '^#[0-9A-Fa-f]{6}$');
+
+-- Create updated_at trigger for projects
+CREATE TRIGGER update_projects_updated_at BEFORE UPDATE ON projects
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Tasks optionally belong to a project; deleting a project is handled by the API
+ALTER TABLE tasks ADD COLUMN project_id UUID REFERENCES projects(id) ON DELETE SET NULL;
+CREATE INDEX idx_tasks_project_id ON tasks(project_id);
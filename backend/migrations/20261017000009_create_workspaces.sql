@@ -0,0 +1,82 @@
+-- Create Workspaces, members and invitations
+-- Roles are declared from least to most privileged so they compare in that order
+CREATE TYPE workspace_role AS ENUM ('viewer', 'member', 'admin', 'owner');
+
+CREATE TABLE workspaces (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL,
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Every user has exactly one private workspace that can't be shared or deleted
+    personal BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_workspaces_personal ON workspaces(owner_id) WHERE personal;
+
+ALTER TABLE workspaces ADD CONSTRAINT chk_workspace_name_length
+    CHECK (char_length(name) >= 1 AND char_length(name) <= 100);
+
+CREATE TRIGGER update_workspaces_updated_at BEFORE UPDATE ON workspaces
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE workspace_members (
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role workspace_role NOT NULL DEFAULT 'member',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (workspace_id, user_id)
+);
+
+CREATE INDEX idx_workspace_members_user_id ON workspace_members(user_id);
+
+-- Only the SHA-256 of an invitation token is stored
+CREATE TABLE workspace_invitations (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    role workspace_role NOT NULL DEFAULT 'member',
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_workspace_invitations_workspace_id ON workspace_invitations(workspace_id);
+
+ALTER TABLE workspace_invitations ADD CONSTRAINT chk_invitation_role
+    CHECK (role <> 'owner');
+
+-- Existing users get a personal workspace holding their tasks and projects
+INSERT INTO workspaces (name, owner_id, personal)
+SELECT 'Personal', id, TRUE FROM users;
+
+INSERT INTO workspace_members (workspace_id, user_id, role)
+SELECT id, owner_id, 'owner' FROM workspaces;
+
+ALTER TABLE tasks ADD COLUMN workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE;
+ALTER TABLE projects ADD COLUMN workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE;
+
+-- Backfill without touching updated_at
+ALTER TABLE tasks DISABLE TRIGGER update_tasks_updated_at;
+ALTER TABLE projects DISABLE TRIGGER update_projects_updated_at;
+
+UPDATE tasks t SET workspace_id = w.id
+FROM workspaces w WHERE w.owner_id = t.user_id AND w.personal;
+
+UPDATE projects p SET workspace_id = w.id
+FROM workspaces w WHERE w.owner_id = p.user_id AND w.personal;
+
+ALTER TABLE tasks ENABLE TRIGGER update_tasks_updated_at;
+ALTER TABLE projects ENABLE TRIGGER update_projects_updated_at;
+
+ALTER TABLE tasks ALTER COLUMN workspace_id SET NOT NULL;
+ALTER TABLE projects ALTER COLUMN workspace_id SET NOT NULL;
+
+CREATE INDEX idx_tasks_workspace_id ON tasks(workspace_id);
+CREATE INDEX idx_projects_workspace_id ON projects(workspace_id, sort_order);
+
+-- Project names are now unique within a workspace
+DROP INDEX idx_projects_user_name;
+CREATE UNIQUE INDEX idx_projects_workspace_name ON projects(workspace_id, LOWER(name));
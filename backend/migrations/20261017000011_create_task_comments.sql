@@ -0,0 +1,31 @@
+-- Create task comments with threaded replies and edit history
+CREATE TABLE task_comments (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Replies outlive the comment they answer
+    reply_to UUID REFERENCES task_comments(id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    edited_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_task_comments_task_id ON task_comments(task_id, created_at, id);
+CREATE INDEX idx_task_comments_reply_to ON task_comments(reply_to);
+
+ALTER TABLE task_comments ADD CONSTRAINT chk_comment_body_length
+    CHECK (char_length(body) >= 1 AND char_length(body) <= 5000);
+
+CREATE TRIGGER update_task_comments_updated_at BEFORE UPDATE ON task_comments
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Previous bodies of edited comments, oldest first
+CREATE TABLE task_comment_edits (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    comment_id UUID NOT NULL REFERENCES task_comments(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    edited_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_task_comment_edits_comment_id ON task_comment_edits(comment_id, edited_at);
@@ -0,0 +1,17 @@
+-- Create mentions of users in task descriptions and comments
+CREATE TABLE mentions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    -- NULL when the mention is in the task description
+    comment_id UUID REFERENCES task_comments(id) ON DELETE CASCADE,
+    mentioned_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    excerpt TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- A user is mentioned at most once per description or comment
+CREATE UNIQUE INDEX idx_mentions_description ON mentions(task_id, user_id) WHERE comment_id IS NULL;
+CREATE UNIQUE INDEX idx_mentions_comment ON mentions(comment_id, user_id) WHERE comment_id IS NOT NULL;
+
+CREATE INDEX idx_mentions_user_id ON mentions(user_id, created_at DESC, id DESC);
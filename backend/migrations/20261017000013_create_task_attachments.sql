@@ -0,0 +1,34 @@
+-- Create file attachments on tasks; the content lives in the storage backend
+CREATE TABLE task_attachments (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    -- Files outlive their uploader's account
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes >= 0),
+    storage_key TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_task_attachments_task_id ON task_attachments(task_id, created_at, id);
+CREATE INDEX idx_task_attachments_uploaded_by ON task_attachments(uploaded_by);
+
+-- Blobs whose attachment row is gone, however it was deleted (directly or
+-- through a task, project or workspace cascade), waiting to be removed from storage
+CREATE TABLE attachment_blob_deletions (
+    storage_key TEXT PRIMARY KEY,
+    queued_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE OR REPLACE FUNCTION queue_attachment_blob_deletion()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO attachment_blob_deletions (storage_key) VALUES (OLD.storage_key)
+    ON CONFLICT (storage_key) DO NOTHING;
+    RETURN OLD;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER queue_task_attachment_blob_deletion AFTER DELETE ON task_attachments
+    FOR EACH ROW EXECUTE FUNCTION queue_attachment_blob_deletion();
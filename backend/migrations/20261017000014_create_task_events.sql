@@ -0,0 +1,34 @@
+-- Append-only log of changes to tasks, with field-level diffs
+CREATE TYPE task_event_type AS ENUM ('created', 'updated', 'status_changed', 'deleted');
+
+CREATE TABLE task_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    -- No foreign key: the history outlives the task it describes
+    task_id UUID NOT NULL,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    event_type task_event_type NOT NULL,
+    -- Title when the event happened
+    task_title VARCHAR(120) NOT NULL,
+    -- {"field": {"old": ..., "new": ...}} for each field that changed
+    changes JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_task_events_task_id ON task_events(task_id, created_at DESC, id DESC);
+CREATE INDEX idx_task_events_workspace_id ON task_events(workspace_id, created_at DESC, id DESC);
+
+-- The one update allowed is the ON DELETE SET NULL of actor_id when the
+-- actor's account is deleted
+CREATE OR REPLACE FUNCTION reject_task_event_update()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.actor_id IS NULL AND (to_jsonb(NEW) - 'actor_id') = (to_jsonb(OLD) - 'actor_id') THEN
+        RETURN NEW;
+    END IF;
+    RAISE EXCEPTION 'task_events is append-only';
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER task_events_append_only BEFORE UPDATE ON task_events
+    FOR EACH ROW EXECUTE FUNCTION reject_task_event_update();
@@ -0,0 +1,8 @@
+-- Deleted tasks go to the trash first and are purged after a retention period
+ALTER TABLE tasks ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_tasks_deleted_at ON tasks(deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- 'deleted' now means moved to the trash
+ALTER TYPE task_event_type ADD VALUE 'restored';
+ALTER TYPE task_event_type ADD VALUE 'purged';
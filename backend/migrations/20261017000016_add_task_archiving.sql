@@ -0,0 +1,36 @@
+-- Archived tasks are hidden from task lists by default, whatever their status
+ALTER TABLE tasks ADD COLUMN archived_at TIMESTAMP WITH TIME ZONE;
+CREATE INDEX idx_tasks_archived_at ON tasks(archived_at) WHERE archived_at IS NOT NULL;
+
+-- When the task was last marked done; NULL while it is open
+ALTER TABLE tasks ADD COLUMN completed_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE tasks DISABLE TRIGGER update_tasks_updated_at;
+UPDATE tasks SET completed_at = updated_at WHERE status = 'done';
+ALTER TABLE tasks ENABLE TRIGGER update_tasks_updated_at;
+
+CREATE INDEX idx_tasks_completed_at ON tasks(completed_at) WHERE completed_at IS NOT NULL AND archived_at IS NULL;
+
+CREATE OR REPLACE FUNCTION set_task_completed_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.status != 'done' THEN
+        NEW.completed_at = NULL;
+    ELSIF TG_OP = 'INSERT' THEN
+        NEW.completed_at = NOW();
+    ELSIF OLD.status != 'done' THEN
+        NEW.completed_at = NOW();
+    END IF;
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER set_tasks_completed_at BEFORE INSERT OR UPDATE OF status ON tasks
+    FOR EACH ROW EXECUTE FUNCTION set_task_completed_at();
+
+-- Days after which a user's done tasks are archived automatically; NULL turns it off
+ALTER TABLE users ADD COLUMN auto_archive_days INTEGER DEFAULT 30
+    CHECK (auto_archive_days IS NULL OR auto_archive_days > 0);
+
+ALTER TYPE task_event_type ADD VALUE 'archived';
+ALTER TYPE task_event_type ADD VALUE 'unarchived';
@@ -0,0 +1,16 @@
+-- Order of a task among tasks of the same status, e.g. within a Kanban column.
+-- A fractional index: moves take the midpoint of their new neighbours, so only
+-- the moved task is written. New tasks go last, by creation time.
+ALTER TABLE tasks ADD COLUMN position DOUBLE PRECISION;
+
+ALTER TABLE tasks DISABLE TRIGGER update_tasks_updated_at;
+ALTER TABLE tasks DISABLE TRIGGER bump_tasks_version;
+UPDATE tasks SET position = EXTRACT(EPOCH FROM created_at);
+ALTER TABLE tasks ENABLE TRIGGER update_tasks_updated_at;
+ALTER TABLE tasks ENABLE TRIGGER bump_tasks_version;
+
+ALTER TABLE tasks
+    ALTER COLUMN position SET DEFAULT EXTRACT(EPOCH FROM clock_timestamp()),
+    ALTER COLUMN position SET NOT NULL;
+
+CREATE INDEX idx_tasks_position ON tasks(workspace_id, status, position) WHERE deleted_at IS NULL;
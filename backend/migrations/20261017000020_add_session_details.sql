@@ -0,0 +1,7 @@
+-- Where and when sessions are used, so users can tell their logins apart
+ALTER TABLE sessions
+    ADD COLUMN user_agent TEXT,
+    ADD COLUMN ip_address TEXT,
+    ADD COLUMN last_seen_at TIMESTAMP WITH TIME ZONE DEFAULT NOW();
+
+UPDATE sessions SET last_seen_at = COALESCE(refreshed_at, created_at);
@@ -0,0 +1,21 @@
+-- When users proved they own their email address. Accounts from before
+-- verification existed count as verified.
+ALTER TABLE users ADD COLUMN email_verified_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE users DISABLE TRIGGER update_users_updated_at;
+UPDATE users SET email_verified_at = created_at;
+ALTER TABLE users ENABLE TRIGGER update_users_updated_at;
+
+-- Create email verification links. Each is for the address it was sent to;
+-- only the token's hash is stored and a token works once, until it expires.
+CREATE TABLE email_verifications (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_email_verifications_user_id ON email_verifications(user_id, created_at DESC);
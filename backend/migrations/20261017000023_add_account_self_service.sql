@@ -0,0 +1,15 @@
+-- Accounts their owner deleted; the scheduler removes them for good once the
+-- grace period is over
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_users_deleted_at ON users(deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- Comments in other people's workspaces outlive their author's account
+ALTER TABLE task_comments ALTER COLUMN user_id DROP NOT NULL;
+ALTER TABLE task_comments DROP CONSTRAINT task_comments_user_id_fkey;
+ALTER TABLE task_comments ADD CONSTRAINT task_comments_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL;
+
+-- Links that move the account to the address they were sent to, rather than
+-- verify the address it already has
+ALTER TABLE email_verifications ADD COLUMN changes_email BOOLEAN NOT NULL DEFAULT FALSE;
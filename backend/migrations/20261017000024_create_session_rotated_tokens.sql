@@ -0,0 +1,9 @@
+-- Hashes of the refresh tokens a session has rotated away from. Only a replay
+-- of one of these revokes the session; any other wrong token is just invalid.
+CREATE TABLE session_rotated_tokens (
+    token_hash TEXT PRIMARY KEY,
+    session_id UUID NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    rotated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_session_rotated_tokens_session_id ON session_rotated_tokens(session_id);
@@ -1,3 +1,4 @@
 pub mod connection;
+pub mod task_query;
 
 pub use connection::*;
\ No newline at end of file
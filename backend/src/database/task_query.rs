@@ -0,0 +1,499 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder, Row};
+use std::env;
+use uuid::Uuid;
+
+use crate::models::{AssigneeFilter, FilterCondition, SearchLanguage, TagMatch, Task, TaskFilters, TaskPriority, TaskStatus};
+use crate::services::access::MEMBER_WORKSPACES;
+use crate::utils::cursor;
+
+// Open blockers of the task in the enclosing query; trashed blockers don't count
+pub const OPEN_BLOCKERS_EXISTS: &str = "EXISTS (SELECT 1 FROM task_dependencies d JOIN tasks b ON b.id = d.blocked_by_id WHERE d.task_id = tasks.id AND b.status != 'done' AND b.deleted_at IS NULL)";
+
+// Columns for task reads, including subtask progress, blocked state and comment count
+macro_rules! task_columns {
+    () => {
+        r#"
+        tasks.*,
+        (SELECT COUNT(*) FROM tasks c WHERE c.parent_id = tasks.id AND c.deleted_at IS NULL) AS subtasks_total,
+        (SELECT COUNT(*) FROM tasks c WHERE c.parent_id = tasks.id AND c.deleted_at IS NULL AND c.status = 'done') AS subtasks_done,
+        EXISTS (
+            SELECT 1 FROM task_dependencies d JOIN tasks b ON b.id = d.blocked_by_id
+            WHERE d.task_id = tasks.id AND b.status != 'done' AND b.deleted_at IS NULL
+        ) AS is_blocked,
+        (SELECT COUNT(*) FROM task_comments tc WHERE tc.task_id = tasks.id) AS comment_count"#
+    };
+}
+
+// Base SELECT for task reads
+pub const TASK_SELECT: &str = concat!("SELECT", task_columns!(), "\n    FROM tasks");
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    Title,
+    Status,
+    Priority,
+    DueDate,
+    UpdatedAt,
+    CreatedAt,
+    Relevance,
+    Position,
+}
+
+impl SortField {
+    pub fn parse(value: Option<&str>) -> Self {
+        match value {
+            Some("title") => Self::Title,
+            Some("status") => Self::Status,
+            Some("priority") => Self::Priority,
+            Some("due_date") => Self::DueDate,
+            Some("updated_at") => Self::UpdatedAt,
+            Some("relevance") => Self::Relevance,
+            Some("position") => Self::Position,
+            _ => Self::CreatedAt, // default
+        }
+    }
+
+    // Column for plain sort fields; relevance is computed from the search query
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Status => "status",
+            Self::Priority => "priority",
+            Self::DueDate => "due_date",
+            Self::UpdatedAt => "updated_at",
+            Self::Position => "position",
+            Self::CreatedAt | Self::Relevance => "created_at",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn parse(value: Option<&str>) -> Self {
+        match value {
+            Some("asc") => Self::Asc,
+            _ => Self::Desc, // default
+        }
+    }
+
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+
+    // Comparison that moves past a keyset position in this order
+    fn after(self) -> &'static str {
+        match self {
+            Self::Asc => " > ",
+            Self::Desc => " < ",
+        }
+    }
+}
+
+// Sort key value of the last task on a page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SortKey {
+    Text(String),
+    Status(TaskStatus),
+    Priority(TaskPriority),
+    Time(Option<DateTime<Utc>>),
+    Rank(f32),
+    Position(f64),
+}
+
+impl SortKey {
+    fn matches(&self, field: SortField) -> bool {
+        matches!(
+            (self, field),
+            (Self::Text(_), SortField::Title)
+                | (Self::Status(_), SortField::Status)
+                | (Self::Priority(_), SortField::Priority)
+                | (Self::Time(_), SortField::DueDate)
+                | (Self::Time(Some(_)), SortField::UpdatedAt | SortField::CreatedAt)
+                | (Self::Rank(_), SortField::Relevance)
+                | (Self::Position(_), SortField::Position)
+        )
+    }
+}
+
+// Keyset position encoded in `cursor`/`next_cursor`
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskCursor {
+    sort: SortField,
+    order: SortOrder,
+    key: SortKey,
+    id: Uuid,
+}
+
+/// One page of tasks and the cursor for the next one, if any
+pub struct TaskPage {
+    pub tasks: Vec<Task>,
+    pub next_cursor: Option<String>,
+}
+
+// Options for ts_headline snippets
+const HEADLINE_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=20, MinWords=5";
+
+/// Default text search language from SEARCH_LANGUAGE (es|en), Spanish if unset
+pub fn default_search_language() -> SearchLanguage {
+    env::var("SEARCH_LANGUAGE")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Task list query for one user, built from the request filters.
+/// The page and count queries share the same WHERE clause; pages are read
+/// by offset or, when a cursor is given, by keyset on the sort key plus id.
+pub struct TaskQuery<'a> {
+    user_id: Uuid,
+    filters: &'a TaskFilters,
+    search: Option<&'a str>,
+    search_language: SearchLanguage,
+    sort_field: SortField,
+    sort_order: SortOrder,
+    cursor: Option<TaskCursor>,
+}
+
+impl<'a> TaskQuery<'a> {
+    /// Fails only when the cursor is malformed or was issued for another sort
+    pub fn new(user_id: Uuid, filters: &'a TaskFilters) -> Result<Self, String> {
+        let search = filters.search.as_deref().map(str::trim).filter(|search| !search.is_empty());
+        let mut sort_field = SortField::parse(filters.sort_by.as_deref());
+        if sort_field == SortField::Relevance && search.is_none() {
+            sort_field = SortField::CreatedAt;
+        }
+        // Manual order reads top to bottom unless asked otherwise
+        let sort_order = match (sort_field, filters.sort_order.as_deref()) {
+            (SortField::Position, None) => SortOrder::Asc,
+            (_, order) => SortOrder::parse(order),
+        };
+
+        // An empty cursor starts a keyset listing from the first row
+        let cursor = match filters.cursor.as_deref().map(str::trim) {
+            Some(value) if !value.is_empty() => {
+                let cursor: TaskCursor = cursor::decode(value)?;
+                if cursor.sort != sort_field || cursor.order != sort_order || !cursor.key.matches(sort_field) {
+                    return Err("Cursor does not match the requested sort".to_string());
+                }
+                Some(cursor)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            user_id,
+            filters,
+            search,
+            search_language: filters.search_lang.unwrap_or_else(default_search_language),
+            sort_field,
+            sort_order,
+            cursor,
+        })
+    }
+
+    /// Reads `limit` tasks and, if more follow, the cursor positioned after them
+    pub async fn fetch_page(&self, pool: &PgPool, limit: u32, offset: u32) -> Result<TaskPage, sqlx::Error> {
+        let rows = self.page_query(limit + 1, offset).build().fetch_all(pool).await?;
+        let limit = limit as usize;
+
+        let next_cursor = match rows.get(limit.wrapping_sub(1)) {
+            Some(last) if rows.len() > limit => Some(cursor::encode(&TaskCursor {
+                sort: self.sort_field,
+                order: self.sort_order,
+                key: self.read_sort_key(last)?,
+                id: last.try_get("id")?,
+            })),
+            _ => None,
+        };
+
+        let tasks = rows
+            .iter()
+            .take(limit)
+            .map(Task::from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TaskPage { tasks, next_cursor })
+    }
+
+    pub fn page_query(&self, limit: u32, offset: u32) -> QueryBuilder<'static, Postgres> {
+        let mut query = QueryBuilder::new(concat!("SELECT", task_columns!(), ",\n        "));
+        self.push_sort_expr(&mut query);
+        query.push(" AS sort_key FROM tasks");
+        self.push_where(&mut query);
+
+        if let Some(cursor) = &self.cursor {
+            self.push_keyset(&mut query, cursor);
+        }
+
+        // Ties and missing due dates are ordered by id so every row has one position
+        query.push(" ORDER BY ");
+        self.push_sort_expr(&mut query);
+        query
+            .push(" ")
+            .push(self.sort_order.keyword())
+            .push(" NULLS LAST, id ")
+            .push(self.sort_order.keyword());
+        query.push(" LIMIT ").push_bind(limit as i64);
+        if self.cursor.is_none() {
+            query.push(" OFFSET ").push_bind(offset as i64);
+        }
+        query
+    }
+
+    pub fn count_query(&self) -> QueryBuilder<'static, Postgres> {
+        let mut query = QueryBuilder::new("SELECT COUNT(*) FROM tasks");
+        self.push_where(&mut query);
+        query
+    }
+
+    /// Snippets for the listed tasks, only when searching
+    pub fn highlights_query(&self, task_ids: &[Uuid]) -> Option<QueryBuilder<'static, Postgres>> {
+        let search = self.search?;
+        let config = self.search_language.config();
+
+        let mut query = QueryBuilder::new(format!(
+            "SELECT id AS task_id, \
+                ts_headline('{config}', title, search_query, '{options}') AS title, \
+                ts_headline('{config}', description, search_query, '{options}') AS description",
+            config = config,
+            options = HEADLINE_OPTIONS,
+        ));
+        query.push(" FROM tasks, ");
+        self.push_tsquery(&mut query, search);
+        query.push(" AS search_query WHERE id = ANY(").push_bind(task_ids.to_vec()).push(")");
+        Some(query)
+    }
+
+    fn push_sort_expr(&self, query: &mut QueryBuilder<'static, Postgres>) {
+        match (self.sort_field, self.search) {
+            (SortField::Relevance, Some(search)) => {
+                query.push("ts_rank_cd(search_vector, ");
+                self.push_tsquery(query, search);
+                query.push(")");
+            }
+            (field, _) => {
+                query.push(field.column());
+            }
+        }
+    }
+
+    fn push_keyset(&self, query: &mut QueryBuilder<'static, Postgres>, cursor: &TaskCursor) {
+        let after = self.sort_order.after();
+
+        query.push(" AND (");
+        match &cursor.key {
+            // Only rows without a due date are left, ordered by id
+            SortKey::Time(None) => {
+                self.push_sort_expr(query);
+                query.push(" IS NULL AND id").push(after).push_bind(cursor.id);
+            }
+            key => {
+                self.push_sort_expr(query);
+                query.push(after);
+                push_sort_key(query, key);
+                query.push(" OR (");
+                self.push_sort_expr(query);
+                query.push(" = ");
+                push_sort_key(query, key);
+                query.push(" AND id").push(after).push_bind(cursor.id).push(")");
+                if self.sort_field == SortField::DueDate {
+                    query.push(" OR due_date IS NULL");
+                }
+            }
+        }
+        query.push(")");
+    }
+
+    fn read_sort_key(&self, row: &PgRow) -> Result<SortKey, sqlx::Error> {
+        Ok(match self.sort_field {
+            SortField::Title => SortKey::Text(row.try_get("sort_key")?),
+            SortField::Status => SortKey::Status(row.try_get("sort_key")?),
+            SortField::Priority => SortKey::Priority(row.try_get("sort_key")?),
+            SortField::DueDate | SortField::UpdatedAt | SortField::CreatedAt => SortKey::Time(row.try_get("sort_key")?),
+            SortField::Relevance => SortKey::Rank(row.try_get("sort_key")?),
+            SortField::Position => SortKey::Position(row.try_get("sort_key")?),
+        })
+    }
+
+    fn push_tsquery(&self, query: &mut QueryBuilder<'static, Postgres>, search: &str) {
+        query
+            .push(format!("websearch_to_tsquery('{}', ", self.search_language.config()))
+            .push_bind(search.to_string())
+            .push(")");
+    }
+
+    fn push_where(&self, query: &mut QueryBuilder<'static, Postgres>) {
+        let filters = self.filters;
+
+        // Tasks of every workspace the user belongs to, leaving out the trash
+        query
+            .push(format!(" WHERE workspace_id IN ({}", MEMBER_WORKSPACES))
+            .push_bind(self.user_id)
+            .push(") AND deleted_at IS NULL");
+
+        if let Some(workspace_id) = filters.workspace_id {
+            query.push(" AND workspace_id = ").push_bind(workspace_id);
+        }
+
+        if filters.include_archived != Some(true) {
+            query.push(" AND archived_at IS NULL");
+        }
+
+        match filters.assignee {
+            Some(AssigneeFilter::Me) => {
+                query.push(" AND assignee_id = ").push_bind(self.user_id);
+            }
+            Some(AssigneeFilter::User(assignee_id)) => {
+                query.push(" AND assignee_id = ").push_bind(assignee_id);
+            }
+            Some(AssigneeFilter::Unassigned) => {
+                query.push(" AND assignee_id IS NULL");
+            }
+            None => {}
+        }
+
+        if let Some(status) = &filters.status {
+            query.push(" AND ");
+            push_condition(query, &FilterCondition::Status { values: status.0.clone(), negated: false });
+        }
+        if let Some(status) = &filters.status_not {
+            query.push(" AND ");
+            push_condition(query, &FilterCondition::Status { values: status.0.clone(), negated: true });
+        }
+        if let Some(priority) = &filters.priority {
+            query.push(" AND ");
+            push_condition(query, &FilterCondition::Priority { values: priority.0.clone(), negated: false });
+        }
+        if let Some(priority) = &filters.priority_not {
+            query.push(" AND ");
+            push_condition(query, &FilterCondition::Priority { values: priority.0.clone(), negated: true });
+        }
+
+        if let Some(search) = self.search {
+            query.push(" AND search_vector @@ ");
+            self.push_tsquery(query, search);
+        }
+
+        if let Some(tag) = &filters.tag {
+            query.push(" AND tags::text ILIKE ").push_bind(format!("%{}%", tag));
+        }
+        if let Some(tags) = &filters.tags {
+            query.push(" AND ");
+            push_tags(query, &tags.0, filters.tags_mode.unwrap_or_default());
+        }
+        if let Some(tags) = &filters.tags_not {
+            query.push(" AND NOT ");
+            push_tags(query, &tags.0, TagMatch::Any);
+        }
+
+        if let Some(group) = &filters.or {
+            query.push(" AND (");
+            for (i, condition) in group.0.iter().enumerate() {
+                if i > 0 {
+                    query.push(" OR ");
+                }
+                push_condition(query, condition);
+            }
+            query.push(")");
+        }
+
+        if let Some(created_from) = filters.created_from {
+            query.push(" AND created_at >= ").push_bind(created_from);
+        }
+        if let Some(created_to) = filters.created_to {
+            query.push(" AND created_at <= ").push_bind(created_to);
+        }
+        if let Some(due_from) = filters.due_from {
+            query.push(" AND due_date >= ").push_bind(due_from);
+        }
+        if let Some(due_to) = filters.due_to {
+            query.push(" AND due_date <= ").push_bind(due_to);
+        }
+
+        if let Some(parent_id) = filters.parent_id {
+            query.push(" AND parent_id = ").push_bind(parent_id);
+        }
+        if filters.root_only == Some(true) {
+            query.push(" AND parent_id IS NULL");
+        }
+
+        if let Some(project_id) = filters.project_id {
+            query.push(" AND project_id = ").push_bind(project_id);
+        }
+
+        match filters.blocked {
+            Some(true) => {
+                query.push(" AND ").push(OPEN_BLOCKERS_EXISTS);
+            }
+            Some(false) => {
+                query.push(" AND NOT ").push(OPEN_BLOCKERS_EXISTS);
+            }
+            None => {}
+        }
+    }
+}
+
+fn push_sort_key(query: &mut QueryBuilder<'static, Postgres>, key: &SortKey) {
+    match key {
+        SortKey::Text(value) => query.push_bind(value.clone()),
+        SortKey::Status(value) => query.push_bind(value.clone()),
+        SortKey::Priority(value) => query.push_bind(value.clone()),
+        SortKey::Time(value) => query.push_bind(*value),
+        SortKey::Rank(value) => query.push_bind(*value),
+        SortKey::Position(value) => query.push_bind(*value),
+    };
+}
+
+fn push_condition(query: &mut QueryBuilder<'static, Postgres>, condition: &FilterCondition) {
+    match condition {
+        FilterCondition::Status { values, negated } => {
+            query.push(if *negated { "status NOT IN (" } else { "status IN (" });
+            let mut list = query.separated(", ");
+            for value in values {
+                list.push_bind(value.clone());
+            }
+            list.push_unseparated(")");
+        }
+        FilterCondition::Priority { values, negated } => {
+            query.push(if *negated { "priority NOT IN (" } else { "priority IN (" });
+            let mut list = query.separated(", ");
+            for value in values {
+                list.push_bind(value.clone());
+            }
+            list.push_unseparated(")");
+        }
+        FilterCondition::Tags { values, negated } => {
+            if *negated {
+                query.push("NOT ");
+            }
+            push_tags(query, values, TagMatch::Any);
+        }
+    }
+}
+
+// Exact tag match against the JSONB array; missing tags count as an empty array
+fn push_tags(query: &mut QueryBuilder<'static, Postgres>, tags: &[String], mode: TagMatch) {
+    let operator = match mode {
+        TagMatch::Any => " ?| ",
+        TagMatch::All => " ?& ",
+    };
+    query
+        .push("(COALESCE(tags, '[]'::jsonb)")
+        .push(operator)
+        .push_bind(tags.to_vec())
+        .push(")");
+}
@@ -1,18 +1,74 @@
-use actix_web::{web, post, get, HttpResponse, Result};
+use actix_web::{web, post, get, put, delete, HttpRequest, HttpResponse, Result};
 use serde_json::json;
 use sqlx::PgPool;
 use validator::Validate;
 use bcrypt::{hash, verify, DEFAULT_COST};
 use uuid::Uuid;
 
-use crate::models::{User, CreateUserRequest, LoginRequest, UserResponse};
-use crate::utils::jwt::create_token;
+use crate::models::{
+    User, CreateUserRequest, LoginRequest, ForgotPasswordRequest, ResetPasswordRequest, VerifyEmailRequest,
+    ResendVerificationRequest, RefreshTokenRequest, LogoutQuery, UserResponse, UserSettings, UpdateUserSettingsRequest,
+    UpdateProfileRequest, ChangePasswordRequest, ChangeEmailRequest, DeleteAccountRequest,
+};
+use crate::utils::jwt::{access_token_ttl, create_token};
 use crate::utils::response::*;
-use crate::middleware::auth::AuthenticatedUser;
+use crate::middleware::auth::{AuthenticatedUser, SessionUser};
+use crate::services::accounts;
+use crate::services::email_verifications::{self, UnverifiedAccess};
+use crate::services::mailer::{self, Email, Mailer};
+use crate::services::notification_service::NotificationService;
+use crate::services::password_resets;
+use crate::services::sessions::{self, RefreshError, SessionClient};
+use crate::services::workspaces;
+use std::sync::Arc;
+
+// What clients get whenever a session hands out tokens: a short-lived access
+// token and the refresh token that replaces it
+fn session_tokens(user: User, session_id: Uuid, refresh_token: String) -> Result<serde_json::Value> {
+    let token = create_token(&user.email, &user.id, &session_id)?;
+
+    Ok(json!({
+        "user": UserResponse::from(user),
+        "token": token,
+        "refresh_token": refresh_token,
+        "expires_in": access_token_ttl().num_seconds()
+    }))
+}
+
+// Emails `user` a link verifying their current address, unless they have been
+// sent too many lately
+async fn send_verification(pool: &PgPool, mailer: Arc<dyn Mailer>, user: &User) -> std::result::Result<(), sqlx::Error> {
+    if let Some(token) = email_verifications::issue(pool, user.id, &user.email).await? {
+        mailer::send_later(mailer, Email {
+            to: user.email.clone(),
+            subject: "Verify your Taskmaster email address".to_string(),
+            body: format!(
+                "Hi {},\n\nConfirm this is your email address by following this link:\n{}/verify-email?token={}\n\n\
+                 It expires in {} hours.",
+                user.name,
+                mailer::app_url(),
+                token,
+                email_verifications::TOKEN_TTL_HOURS
+            ),
+        });
+    }
+    Ok(())
+}
+
+// Changes to the account itself are confirmed with the user's password
+fn password_matches(user: &User, password: &str) -> bool {
+    verify(password, &user.password_hash).unwrap_or(false)
+}
+
+fn invalid_password_response() -> HttpResponse {
+    bad_request_response(ApiError::new("INVALID_PASSWORD", "Password is incorrect"))
+}
 
 #[post("/register")]
 pub async fn register(
+    req: HttpRequest,
     pool: web::Data<PgPool>,
+    mailer: web::Data<dyn Mailer>,
     user_data: web::Json<CreateUserRequest>,
 ) -> Result<HttpResponse> {
     if let Err(errors) = user_data.validate() {
@@ -58,6 +114,10 @@ pub async fn register(
 
     match result {
         Ok(_) => {
+            if let Err(e) = workspaces::personal_workspace(pool.get_ref(), user_id).await {
+                return Ok(internal_error_response(&format!("Failed to create workspace: {}", e)));
+            }
+
             let user = sqlx::query_as::<_, User>(
                 "SELECT * FROM users WHERE id = $1"
             )
@@ -66,13 +126,25 @@ pub async fn register(
             .await
             .unwrap();
 
-            let token = create_token(&user.email, &user.id)?;
-            let user_response = UserResponse::from(user);
+            // The account is there either way; a lost email can be sent again
+            if let Err(e) = send_verification(pool.get_ref(), mailer.into_inner(), &user).await {
+                tracing::error!("Failed to send verification email to {}: {}", user.email, e);
+            }
+
+            if UnverifiedAccess::of_app(&req) == UnverifiedAccess::Blocked {
+                let response_data = json!({
+                    "user": UserResponse::from(user),
+                    "verification_required": true
+                });
+                return Ok(created_response(response_data, "User registered; verify your email address to log in"));
+            }
 
-            let response_data = json!({
-                "user": user_response,
-                "token": token
-            });
+            let (session_id, refresh_token) = match sessions::create(pool.get_ref(), user.id, &SessionClient::from_request(&req)).await {
+                Ok(session) => session,
+                Err(e) => return Ok(internal_error_response(&format!("Failed to start session: {}", e))),
+            };
+
+            let response_data = session_tokens(user, session_id, refresh_token)?;
 
             Ok(created_response(response_data, "User registered successfully"))
         }
@@ -84,6 +156,7 @@ pub async fn register(
 
 #[post("/login")]
 pub async fn login(
+    req: HttpRequest,
     pool: web::Data<PgPool>,
     login_data: web::Json<LoginRequest>,
 ) -> Result<HttpResponse> {
@@ -119,19 +192,536 @@ pub async fn login(
         return Ok(unauthorized_response("Invalid email or password"));
     }
 
-    let token = create_token(&user.email, &user.id)?;
-    let user_response = UserResponse::from(user);
+    if user.email_verified_at.is_none() && UnverifiedAccess::of_app(&req) == UnverifiedAccess::Blocked {
+        return Ok(HttpResponse::Forbidden()
+            .json(ApiError::new("EMAIL_NOT_VERIFIED", "Verify your email address to log in")));
+    }
+
+    // Logging in during the grace period keeps the account
+    let mut message = "Login successful";
+    if user.deleted_at.is_some() {
+        match accounts::cancel_deletion(pool.get_ref(), user.id).await {
+            Ok(_) => message = "Login successful; account deletion cancelled",
+            Err(e) => return Ok(internal_error_response(&format!("Failed to restore account: {}", e))),
+        }
+    }
+
+    let (session_id, refresh_token) = match sessions::create(pool.get_ref(), user.id, &SessionClient::from_request(&req)).await {
+        Ok(session) => session,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to start session: {}", e))),
+    };
+
+    let response_data = session_tokens(user, session_id, refresh_token)?;
+
+    Ok(ok_response(response_data, message))
+}
+
+#[post("/refresh")]
+pub async fn refresh(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    body: web::Json<RefreshTokenRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let refreshed = match sessions::refresh(pool.get_ref(), &body.refresh_token, &SessionClient::from_request(&req)).await {
+        Ok(refreshed) => refreshed,
+        Err(RefreshError::Invalid) => {
+            return Ok(HttpResponse::Unauthorized()
+                .json(ApiError::new("INVALID_REFRESH_TOKEN", "Invalid or expired refresh token")));
+        }
+        Err(RefreshError::Reused { user_id, session_id }) => {
+            notification_service.close_sessions(user_id, &[session_id]).await;
+            return Ok(HttpResponse::Unauthorized()
+                .json(ApiError::new("REFRESH_TOKEN_REUSED", "Refresh token was already used; the session has been revoked")));
+        }
+        Err(RefreshError::Database(e)) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT * FROM users WHERE id = $1"
+    )
+    .bind(refreshed.user_id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    let user = match user {
+        Ok(user) => user,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let response_data = session_tokens(user, refreshed.session_id, refreshed.refresh_token)?;
+
+    Ok(ok_response(response_data, "Token refreshed successfully"))
+}
+
+#[post("/logout")]
+pub async fn logout(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: SessionUser,
+    query: web::Query<LogoutQuery>,
+) -> Result<HttpResponse> {
+    let revoked = if query.all {
+        sessions::revoke_all(pool.get_ref(), user.0.id).await
+    } else {
+        sessions::revoke(pool.get_ref(), user.0.id, user.1)
+            .await
+            .map(|revoked| if revoked { vec![user.1] } else { Vec::new() })
+    };
+
+    match revoked {
+        Ok(revoked) => {
+            notification_service.close_sessions(user.0.id, &revoked).await;
+            Ok(ok_response(json!({ "revoked_sessions": revoked.len() }), "Logged out successfully"))
+        }
+        Err(e) => Ok(internal_error_response(&format!("Failed to log out: {}", e))),
+    }
+}
+
+#[post("/verify-email")]
+pub async fn verify_email(
+    pool: web::Data<PgPool>,
+    body: web::Json<VerifyEmailRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    match email_verifications::verify(&mut tx, &body.token).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return Ok(bad_request_response(ApiError::new(
+                "INVALID_VERIFICATION_TOKEN",
+                "Invalid, expired or already used verification token",
+            )));
+        }
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to verify email: {}", e)));
+    }
+
+    Ok(ok_response(json!({"verified": true}), "Email verified successfully"))
+}
+
+#[post("/verify-email/resend")]
+pub async fn resend_verification(
+    pool: web::Data<PgPool>,
+    mailer: web::Data<dyn Mailer>,
+    body: web::Json<ResendVerificationRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    // The answer is the same whether or not the address has an unverified account
+    let sent = ok_response(
+        json!({"sent": true}),
+        "If an unverified account exists for that email, a verification link has been sent",
+    );
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT * FROM users WHERE email = $1 AND email_verified_at IS NULL AND deleted_at IS NULL"
+    )
+    .bind(&body.email)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let user = match user {
+        Ok(Some(user)) => user,
+        Ok(None) => return Ok(sent),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    match send_verification(pool.get_ref(), mailer.into_inner(), &user).await {
+        Ok(()) => Ok(sent),
+        Err(e) => Ok(internal_error_response(&format!("Failed to send verification email: {}", e))),
+    }
+}
+
+#[post("/password/forgot")]
+pub async fn forgot_password(
+    pool: web::Data<PgPool>,
+    mailer: web::Data<dyn Mailer>,
+    body: web::Json<ForgotPasswordRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    // The answer is the same whether or not the address has an account
+    let sent = ok_response(
+        json!({"sent": true}),
+        "If an account exists for that email, a password reset link has been sent",
+    );
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT * FROM users WHERE email = $1 AND deleted_at IS NULL"
+    )
+    .bind(&body.email)
+    .fetch_optional(pool.get_ref())
+    .await;
 
-    let response_data = json!({
-        "user": user_response,
-        "token": token
+    let user = match user {
+        Ok(Some(user)) => user,
+        Ok(None) => return Ok(sent),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let token = match password_resets::issue(pool.get_ref(), user.id).await {
+        Ok(Some(token)) => token,
+        Ok(None) => return Ok(sent),
+        Err(e) => return Ok(internal_error_response(&format!("Failed to create password reset: {}", e))),
+    };
+
+    mailer::send_later(mailer.into_inner(), Email {
+        to: user.email,
+        subject: "Reset your Taskmaster password".to_string(),
+        body: format!(
+            "Hi {},\n\nUse this link to choose a new password:\n{}/reset-password?token={}\n\n\
+             It works once and expires in {} minutes. If you didn't ask for it, you can ignore this email.",
+            user.name,
+            mailer::app_url(),
+            token,
+            password_resets::TOKEN_TTL_MINUTES
+        ),
     });
 
-    Ok(ok_response(response_data, "Login successful"))
+    Ok(sent)
+}
+
+#[post("/password/reset")]
+pub async fn reset_password(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    body: web::Json<ResetPasswordRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let password_hash = match hash(&body.password, DEFAULT_COST) {
+        Ok(hash) => hash,
+        Err(_) => {
+            return Ok(internal_error_response("Failed to hash password"));
+        }
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let user_id = match password_resets::consume(&mut tx, &body.token).await {
+        Ok(Some(user_id)) => user_id,
+        Ok(None) => {
+            return Ok(bad_request_response(ApiError::new(
+                "INVALID_RESET_TOKEN",
+                "Invalid, expired or already used reset token",
+            )));
+        }
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let updated = sqlx::query("UPDATE users SET password_hash = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(&password_hash)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = updated {
+        return Ok(internal_error_response(&format!("Failed to reset password: {}", e)));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to reset password: {}", e)));
+    }
+
+    // Whoever knew the old password is logged out everywhere
+    match sessions::revoke_all(pool.get_ref(), user_id).await {
+        Ok(revoked) => notification_service.close_sessions(user_id, &revoked).await,
+        Err(e) => tracing::error!("Failed to revoke sessions after password reset: {}", e),
+    }
+
+    Ok(ok_response(json!({"reset": true}), "Password reset successfully"))
+}
+
+#[get("/sessions")]
+pub async fn get_sessions(
+    pool: web::Data<PgPool>,
+    user: SessionUser,
+) -> Result<HttpResponse> {
+    match sessions::list(pool.get_ref(), user.0.id, user.1).await {
+        Ok(sessions) => Ok(ok_response(sessions, "Sessions retrieved successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to fetch sessions: {}", e))),
+    }
+}
+
+#[delete("/sessions/{id}")]
+pub async fn revoke_session(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: SessionUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let session_id = path.into_inner();
+
+    match sessions::revoke(pool.get_ref(), user.0.id, session_id).await {
+        Ok(true) => {
+            notification_service.close_sessions(user.0.id, &[session_id]).await;
+            Ok(ok_response(json!({"revoked": true}), "Session revoked successfully"))
+        }
+        Ok(false) => Ok(not_found_response("session")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to revoke session: {}", e))),
+    }
 }
 
 #[get("/me")]
-pub async fn me(user: AuthenticatedUser) -> Result<HttpResponse> {
+pub async fn me(user: SessionUser) -> Result<HttpResponse> {
     let user_response = UserResponse::from(user.0);
     Ok(ok_response(user_response, "User information retrieved successfully"))
-}
\ No newline at end of file
+}
+
+#[put("/me")]
+pub async fn update_me(
+    pool: web::Data<PgPool>,
+    user: SessionUser,
+    body: web::Json<UpdateProfileRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let updated = sqlx::query_as::<_, User>(
+        "UPDATE users SET name = $2 WHERE id = $1 RETURNING *"
+    )
+    .bind(user.0.id)
+    .bind(&body.name)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match updated {
+        Ok(updated) => Ok(ok_response(UserResponse::from(updated), "Profile updated successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to update profile: {}", e))),
+    }
+}
+
+#[post("/me/password")]
+pub async fn change_password(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: SessionUser,
+    body: web::Json<ChangePasswordRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    if !password_matches(&user.0, &body.current_password) {
+        return Ok(invalid_password_response());
+    }
+
+    let password_hash = match hash(&body.new_password, DEFAULT_COST) {
+        Ok(hash) => hash,
+        Err(_) => {
+            return Ok(internal_error_response("Failed to hash password"));
+        }
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let updated = sqlx::query("UPDATE users SET password_hash = $2 WHERE id = $1")
+        .bind(user.0.id)
+        .bind(&password_hash)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = updated {
+        return Ok(internal_error_response(&format!("Failed to change password: {}", e)));
+    }
+
+    // Reset links sent before the change can't undo it
+    let invalidated = sqlx::query("UPDATE password_resets SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL")
+        .bind(user.0.id)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = invalidated {
+        return Ok(internal_error_response(&format!("Failed to change password: {}", e)));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to change password: {}", e)));
+    }
+
+    // Every other device has to log in with the new password
+    match sessions::revoke_others(pool.get_ref(), user.0.id, user.1).await {
+        Ok(revoked) => {
+            notification_service.close_sessions(user.0.id, &revoked).await;
+            Ok(ok_response(json!({ "revoked_sessions": revoked.len() }), "Password changed successfully"))
+        }
+        Err(e) => Ok(internal_error_response(&format!("Failed to revoke sessions: {}", e))),
+    }
+}
+
+#[post("/me/email")]
+pub async fn change_email(
+    pool: web::Data<PgPool>,
+    mailer: web::Data<dyn Mailer>,
+    user: SessionUser,
+    body: web::Json<ChangeEmailRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    if !password_matches(&user.0, &body.password) {
+        return Ok(invalid_password_response());
+    }
+
+    if body.email == user.0.email {
+        return Ok(bad_request_response(ApiError::new("EMAIL_UNCHANGED", "That is already your email address")));
+    }
+
+    let taken = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM users WHERE email = $1)")
+        .bind(&body.email)
+        .fetch_one(pool.get_ref())
+        .await;
+
+    match taken {
+        Ok(true) => return Ok(conflict_response("Email already exists")),
+        Ok(false) => {}
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    }
+
+    // The account keeps its address until the new one is verified
+    let token = match email_verifications::issue_email_change(pool.get_ref(), user.0.id, &body.email).await {
+        Ok(Some(token)) => token,
+        Ok(None) => {
+            return Ok(HttpResponse::TooManyRequests()
+                .json(ApiError::new("TOO_MANY_REQUESTS", "Too many verification emails; try again later")));
+        }
+        Err(e) => return Ok(internal_error_response(&format!("Failed to create verification: {}", e))),
+    };
+
+    let mailer = mailer.into_inner();
+    mailer::send_later(mailer.clone(), Email {
+        to: body.email.clone(),
+        subject: "Confirm your new Taskmaster email address".to_string(),
+        body: format!(
+            "Hi {},\n\nFollow this link to use this address for your Taskmaster account:\n{}/verify-email?token={}\n\n\
+             It expires in {} hours.",
+            user.0.name,
+            mailer::app_url(),
+            token,
+            email_verifications::TOKEN_TTL_HOURS
+        ),
+    });
+    mailer::send_later(mailer, Email {
+        to: user.0.email.clone(),
+        subject: "Your Taskmaster email address is being changed".to_string(),
+        body: format!(
+            "Hi {},\n\nSomeone asked to change your Taskmaster email address to {}. It changes once that \
+             address is confirmed. If this wasn't you, reset your password.",
+            user.0.name,
+            body.email
+        ),
+    });
+
+    Ok(ok_response(json!({ "pending_email": body.email }), "Verification link sent to the new address"))
+}
+
+#[delete("/me")]
+pub async fn delete_me(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: SessionUser,
+    body: web::Json<DeleteAccountRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    if !password_matches(&user.0, &body.password) {
+        return Ok(invalid_password_response());
+    }
+
+    // Deleting the account would take the other members' work with it
+    match accounts::owns_shared_workspace(pool.get_ref(), user.0.id).await {
+        Ok(true) => {
+            return Ok(conflict_response(
+                "Transfer or delete the workspaces you share with others before deleting your account",
+            ))
+        }
+        Ok(false) => {}
+        Err(e) => return Ok(internal_error_response(&format!("Failed to delete account: {}", e))),
+    }
+
+    let deleted_at = match accounts::schedule_deletion(pool.get_ref(), user.0.id).await {
+        Ok(deleted_at) => deleted_at,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to delete account: {}", e))),
+    };
+
+    match sessions::revoke_all(pool.get_ref(), user.0.id).await {
+        Ok(revoked) => notification_service.close_sessions(user.0.id, &revoked).await,
+        Err(e) => tracing::error!("Failed to revoke sessions of deleted account: {}", e),
+    }
+
+    Ok(ok_response(
+        json!({ "deleted_at": deleted_at }),
+        "Account scheduled for deletion; log in again before it is removed to keep it",
+    ))
+}
+
+#[get("/me/settings")]
+pub async fn get_settings(user: AuthenticatedUser) -> Result<HttpResponse> {
+    let settings = UserSettings {
+        auto_archive_days: user.0.auto_archive_days,
+    };
+    Ok(ok_response(settings, "Settings retrieved successfully"))
+}
+
+#[put("/me/settings")]
+pub async fn update_settings(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    settings: web::Json<UpdateUserSettingsRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = settings.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let auto_archive_days = match settings.auto_archive_days {
+        Some(auto_archive_days) => auto_archive_days,
+        None => {
+            let settings = UserSettings { auto_archive_days: user.0.auto_archive_days };
+            return Ok(ok_response(settings, "Settings updated successfully"));
+        }
+    };
+
+    let settings = sqlx::query_as::<_, UserSettings>(
+        "UPDATE users SET auto_archive_days = $2 WHERE id = $1 RETURNING auto_archive_days"
+    )
+    .bind(user.0.id)
+    .bind(auto_archive_days)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match settings {
+        Ok(settings) => Ok(ok_response(settings, "Settings updated successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to update settings: {}", e))),
+    }
+}
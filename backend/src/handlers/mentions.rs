@@ -0,0 +1,57 @@
+use actix_web::{web, get, HttpResponse, Result};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{Mention, MentionQuery, MentionsPage};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::access::MEMBER_WORKSPACES;
+use crate::utils::cursor;
+use crate::utils::response::*;
+
+#[get("")]
+pub async fn get_mentions(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<MentionQuery>,
+) -> Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let position = match cursor::position::<Uuid>(query.cursor.as_deref()) {
+        Ok(position) => position,
+        Err(message) => return Ok(bad_request_response(ApiError::new("INVALID_CURSOR", &message))),
+    };
+
+    // Mentions on tasks the user can no longer see, or that are in the trash, are left out
+    let mentions = sqlx::query_as::<_, Mention>(
+        &format!(
+            r#"
+            SELECT mn.id, mn.task_id, t.title AS task_title, mn.comment_id, mn.mentioned_by,
+                u.name AS mentioned_by_name, mn.excerpt, mn.created_at
+            FROM mentions mn
+            JOIN tasks t ON t.id = mn.task_id
+            LEFT JOIN users u ON u.id = mn.mentioned_by
+            WHERE mn.user_id = $1
+              AND t.workspace_id IN ({}$1)
+              AND t.deleted_at IS NULL
+              AND ($2::timestamptz IS NULL OR (mn.created_at, mn.id) < ($2, $3))
+            ORDER BY mn.created_at DESC, mn.id DESC
+            LIMIT $4
+            "#,
+            MEMBER_WORKSPACES
+        )
+    )
+    .bind(user.0.id)
+    .bind(position.as_ref().map(|p| p.at))
+    .bind(position.as_ref().map(|p| p.id))
+    .bind(limit + 1)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let mut mentions = match mentions {
+        Ok(mentions) => mentions,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to fetch mentions: {}", e))),
+    };
+
+    let next_cursor = cursor::next_page(&mut mentions, limit, |last| Some((last.created_at, last.id)));
+
+    Ok(ok_response(MentionsPage { mentions, next_cursor }, "Mentions retrieved successfully"))
+}
@@ -1,7 +1,14 @@
 pub mod auth;
 pub mod stats;
 pub mod tasks;
+pub mod task_dependencies;
+pub mod task_comments;
+pub mod task_attachments;
+pub mod task_events;
 pub mod notifications;
+pub mod projects;
+pub mod workspaces;
+pub mod mentions;
 
 use actix_web::{get, HttpResponse, Result};
 use crate::utils::response::*;
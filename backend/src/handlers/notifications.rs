@@ -1,16 +1,28 @@
-use actix_web::{web, HttpRequest, HttpResponse, Result as ActixResult, get, post};
+use actix_web::{web, http::header, HttpRequest, HttpResponse, Result as ActixResult, get, post};
 use actix_web_actors::ws;
 use serde::Deserialize;
 
 use crate::models::notification::MarkAsReadRequest;
 use crate::services::notification_service::{NotificationService, NotificationWebSocket};
 use crate::middleware::auth::AuthenticatedUser;
+use crate::utils::jwt::verify_token;
 use crate::utils::response::{ApiResponse, ApiError};
 
+// Subprotocol used to pass the token during the handshake, as
+// `Sec-WebSocket-Protocol: bearer, <token>`
+const WS_TOKEN_PROTOCOL: &str = "bearer";
+
 #[derive(Deserialize)]
 pub struct NotificationQuery {
     limit: Option<i64>,
     offset: Option<i64>,
+    // Present (even empty) to page by keyset and get a next_cursor
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct WebSocketAuthQuery {
+    token: Option<String>,
 }
 
 // Get user notifications
@@ -20,6 +32,13 @@ pub async fn get_notifications(
     query: web::Query<NotificationQuery>,
     notification_service: web::Data<NotificationService>,
 ) -> ActixResult<HttpResponse, ApiError> {
+    if let Some(cursor) = &query.cursor {
+        let page = notification_service
+            .get_user_notifications_page(user.0.id, query.limit, cursor)
+            .await?;
+        return Ok(HttpResponse::Ok().json(ApiResponse::success(page, "Notifications retrieved successfully")));
+    }
+
     let notifications = notification_service
         .get_user_notifications(user.0.id, query.limit, query.offset)
         .await?;
@@ -56,13 +75,41 @@ pub async fn mark_as_read(
     }), "Notifications marked as read successfully")))
 }
 
+// Token offered during the handshake, from `?token=` or the subprotocol header
+fn handshake_token(req: &HttpRequest) -> Option<String> {
+    if let Ok(query) = web::Query::<WebSocketAuthQuery>::from_query(req.query_string()) {
+        if let Some(token) = query.into_inner().token {
+            return Some(token);
+        }
+    }
+
+    let protocols = req.headers().get(header::SEC_WEBSOCKET_PROTOCOL)?.to_str().ok()?;
+    let mut protocols = protocols.split(',').map(str::trim);
+    protocols.find(|protocol| protocol.eq_ignore_ascii_case(WS_TOKEN_PROTOCOL))?;
+    protocols.next().map(str::to_string)
+}
+
 // WebSocket endpoint
 pub async fn websocket(
     req: HttpRequest,
     stream: web::Payload,
     notification_service: web::Data<NotificationService>,
 ) -> ActixResult<HttpResponse> {
-    let ws_actor = NotificationWebSocket::new(notification_service.get_ref().clone());
-    
-    ws::start(ws_actor, &req, stream)
+    // Sockets without a handshake token must authenticate with their first frame
+    let claims = match handshake_token(&req) {
+        Some(token) => match verify_token(&token) {
+            Ok(claims) => Some(claims),
+            Err(_) => {
+                return Ok(HttpResponse::Unauthorized()
+                    .json(ApiError::new("INVALID_TOKEN", "Invalid or expired token")));
+            }
+        },
+        None => None,
+    };
+
+    let ws_actor = NotificationWebSocket::new(notification_service.get_ref().clone(), claims);
+
+    ws::WsResponseBuilder::new(ws_actor, &req, stream)
+        .protocols(&[WS_TOKEN_PROTOCOL])
+        .start()
 }
@@ -0,0 +1,320 @@
+use actix_web::{web, get, post, put, delete, HttpResponse, Result};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{
+    Project, CreateProjectRequest, UpdateProjectRequest, ProjectFilters, ProjectTasksOnDelete, DeleteProjectQuery,
+    Task, TaskEventType,
+};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::access::{self, Permission, MEMBER_WORKSPACES};
+use crate::services::task_events;
+use crate::services::workspaces;
+use crate::utils::response::*;
+
+// Base SELECT for project reads, including task progress
+const PROJECT_SELECT: &str = r#"
+    SELECT projects.*,
+        (SELECT COUNT(*) FROM tasks t WHERE t.project_id = projects.id AND t.deleted_at IS NULL) AS tasks_total,
+        (SELECT COUNT(*) FROM tasks t WHERE t.project_id = projects.id AND t.deleted_at IS NULL AND t.status = 'done') AS tasks_done
+    FROM projects"#;
+
+pub(crate) async fn project_in_workspace(pool: &PgPool, project_id: Uuid, workspace_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM projects WHERE id = $1 AND workspace_id = $2)"
+    )
+    .bind(project_id)
+    .bind(workspace_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn fetch_project(pool: &PgPool, project_id: Uuid) -> Result<Option<Project>, sqlx::Error> {
+    sqlx::query_as::<_, Project>(&format!("{} WHERE id = $1", PROJECT_SELECT))
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+}
+
+fn is_unique_violation(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Database(e) if e.is_unique_violation())
+}
+
+#[get("")]
+pub async fn get_projects(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<ProjectFilters>,
+) -> Result<HttpResponse> {
+    let include_archived = query.include_archived.unwrap_or(false);
+
+    if let Some(workspace_id) = query.workspace_id {
+        if let Err(e) = access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Read).await {
+            return Ok(e.into_response("workspace"));
+        }
+    }
+
+    let projects = sqlx::query_as::<_, Project>(
+        &format!(
+            r#"{} WHERE workspace_id IN ({}$1) AND ($2 OR NOT archived) AND ($3::uuid IS NULL OR workspace_id = $3)
+            ORDER BY sort_order ASC, created_at ASC"#,
+            PROJECT_SELECT, MEMBER_WORKSPACES
+        )
+    )
+    .bind(user.0.id)
+    .bind(include_archived)
+    .bind(query.workspace_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match projects {
+        Ok(projects) => Ok(ok_response(projects, "Projects retrieved successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to fetch projects: {}", e))),
+    }
+}
+
+#[post("")]
+pub async fn create_project(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    project_data: web::Json<CreateProjectRequest>,
+) -> Result<HttpResponse> {
+    // Trimmed first so a blank name fails validation rather than the DB check
+    let mut project_data = project_data.into_inner();
+    project_data.name = project_data.name.trim().to_string();
+    if let Err(errors) = project_data.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let workspace_id = match project_data.workspace_id {
+        Some(workspace_id) => workspace_id,
+        None => match workspaces::personal_workspace(pool.get_ref(), user.0.id).await {
+            Ok(workspace_id) => workspace_id,
+            Err(e) => return Ok(internal_error_response(&e.to_string())),
+        },
+    };
+
+    if let Err(e) = access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Write).await {
+        return Ok(e.into_response("workspace"));
+    }
+
+    // New projects go last unless a position is given
+    let project_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO projects (user_id, workspace_id, name, color, description, sort_order)
+        VALUES ($1, $2, $3, $4, $5, COALESCE($6, (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM projects WHERE workspace_id = $2)))
+        RETURNING id
+        "#
+    )
+    .bind(user.0.id)
+    .bind(workspace_id)
+    .bind(&project_data.name)
+    .bind(&project_data.color)
+    .bind(&project_data.description)
+    .bind(project_data.sort_order)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match project_id {
+        Ok(project_id) => match fetch_project(pool.get_ref(), project_id).await {
+            Ok(Some(project)) => Ok(created_response(project, "Project created successfully")),
+            Ok(None) => Ok(not_found_response("project")),
+            Err(e) => Ok(internal_error_response(&e.to_string())),
+        },
+        Err(e) if is_unique_violation(&e) => Ok(conflict_response("A project with this name already exists")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to create project: {}", e))),
+    }
+}
+
+#[get("/{id}")]
+pub async fn get_project(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let project_id = path.into_inner();
+
+    if let Err(e) = access::authorize_project(pool.get_ref(), project_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("project"));
+    }
+
+    match fetch_project(pool.get_ref(), project_id).await {
+        Ok(Some(project)) => Ok(ok_response(project, "Project retrieved successfully")),
+        Ok(None) => Ok(not_found_response("project")),
+        Err(e) => Ok(internal_error_response(&e.to_string())),
+    }
+}
+
+#[put("/{id}")]
+pub async fn update_project(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    project_data: web::Json<UpdateProjectRequest>,
+) -> Result<HttpResponse> {
+    let mut project_data = project_data.into_inner();
+    project_data.name = project_data.name.map(|name| name.trim().to_string());
+    if let Err(errors) = project_data.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let project_id = path.into_inner();
+
+    if let Err(e) = access::authorize_project(pool.get_ref(), project_id, user.0.id, Permission::Write).await {
+        return Ok(e.into_response("project"));
+    }
+
+    let mut query: QueryBuilder<Postgres> = QueryBuilder::new("UPDATE projects SET ");
+    let mut updates = query.separated(", ");
+    let mut has_updates = false;
+
+    if let Some(name) = &project_data.name {
+        updates.push("name = ").push_bind_unseparated(name.clone());
+        has_updates = true;
+    }
+    if let Some(color) = &project_data.color {
+        updates.push("color = ").push_bind_unseparated(color.clone());
+        has_updates = true;
+    }
+    if let Some(description) = &project_data.description {
+        updates.push("description = ").push_bind_unseparated(description.clone());
+        has_updates = true;
+    }
+    if let Some(archived) = project_data.archived {
+        updates.push("archived = ").push_bind_unseparated(archived);
+        has_updates = true;
+    }
+    if let Some(sort_order) = project_data.sort_order {
+        updates.push("sort_order = ").push_bind_unseparated(sort_order);
+        has_updates = true;
+    }
+
+    if !has_updates {
+        return Ok(bad_request_response(ApiError::new("NO_UPDATES", "No fields to update")));
+    }
+
+    query.push(" WHERE id = ").push_bind(project_id);
+
+    match query.build().execute(pool.get_ref()).await {
+        Ok(result) if result.rows_affected() == 0 => Ok(not_found_response("project")),
+        Ok(_) => match fetch_project(pool.get_ref(), project_id).await {
+            Ok(Some(project)) => Ok(ok_response(project, "Project updated successfully")),
+            Ok(None) => Ok(not_found_response("project")),
+            Err(e) => Ok(internal_error_response(&e.to_string())),
+        },
+        Err(e) if is_unique_violation(&e) => Ok(conflict_response("A project with this name already exists")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to update project: {}", e))),
+    }
+}
+
+// Moves a project's tasks, trashed ones included, to `move_to` (or out of any
+// project), recording the change for each. Returns how many were moved.
+async fn move_project_tasks(conn: &mut PgConnection, project_id: Uuid, move_to: Option<Uuid>, actor_id: Uuid) -> Result<u64, sqlx::Error> {
+    let before = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE project_id = $1 FOR UPDATE")
+        .bind(project_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let moved = sqlx::query_as::<_, Task>("UPDATE tasks SET project_id = $2 WHERE project_id = $1 RETURNING *")
+        .bind(project_id)
+        .bind(move_to)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    for after in &moved {
+        if let Some(before) = before.iter().find(|task| task.id == after.id) {
+            task_events::record_update(&mut *conn, before, after, actor_id).await?;
+        }
+    }
+
+    Ok(moved.len() as u64)
+}
+
+// Moves a project's tasks to the trash, like deleting each of them; tasks
+// already in the trash stay there as they are. Returns how many were trashed.
+async fn trash_project_tasks(conn: &mut PgConnection, project_id: Uuid, actor_id: Uuid) -> Result<u64, sqlx::Error> {
+    let trashed = sqlx::query_as::<_, Task>(
+        "UPDATE tasks SET deleted_at = NOW() WHERE project_id = $1 AND deleted_at IS NULL RETURNING *"
+    )
+    .bind(project_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for task in &trashed {
+        let changes = task_events::diff(Some(task), None);
+        task_events::record(&mut *conn, task.id, task.workspace_id, &task.title, Some(actor_id), TaskEventType::Deleted, changes).await?;
+    }
+
+    Ok(trashed.len() as u64)
+}
+
+#[delete("/{id}")]
+pub async fn delete_project(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    query: web::Query<DeleteProjectQuery>,
+) -> Result<HttpResponse> {
+    let project_id = path.into_inner();
+
+    // Deleting can take other members' tasks with it, so it's for admins
+    let workspace_id = match access::authorize_project(pool.get_ref(), project_id, user.0.id, Permission::Administer).await {
+        Ok(access) => access.workspace_id,
+        Err(e) => return Ok(e.into_response("project")),
+    };
+
+    if query.tasks == ProjectTasksOnDelete::Move {
+        if let Some(move_to) = query.move_to {
+            let target_valid = move_to != project_id
+                && match project_in_workspace(pool.get_ref(), move_to, workspace_id).await {
+                    Ok(exists) => exists,
+                    Err(e) => return Ok(internal_error_response(&e.to_string())),
+                };
+            if !target_valid {
+                let error = ApiError::new("INVALID_PROJECT", "Tasks can only be moved to another project in the same workspace");
+                return Ok(bad_request_response(error));
+            }
+        }
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let tasks_result = match query.tasks {
+        ProjectTasksOnDelete::Move => move_project_tasks(&mut tx, project_id, query.move_to, user.0.id).await,
+        ProjectTasksOnDelete::Delete => trash_project_tasks(&mut tx, project_id, user.0.id).await,
+    };
+
+    let affected_tasks = match tasks_result {
+        Ok(affected) => affected,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to delete project: {}", e))),
+    };
+
+    let result = sqlx::query("DELETE FROM projects WHERE id = $1")
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = result {
+        return Ok(internal_error_response(&format!("Failed to delete project: {}", e)));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to delete project: {}", e)));
+    }
+
+    let (moved, deleted) = match query.tasks {
+        ProjectTasksOnDelete::Move => (affected_tasks, 0),
+        ProjectTasksOnDelete::Delete => (0, affected_tasks),
+    };
+    let success_data = json!({
+        "deleted": true,
+        "tasks_moved": moved,
+        "tasks_deleted": deleted,
+    });
+    Ok(ok_response(success_data, "Project deleted successfully"))
+}
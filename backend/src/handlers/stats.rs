@@ -1,9 +1,11 @@
 use actix_web::{web, get, HttpResponse, Result};
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Row};
+use sqlx::{FromRow, PgPool, Row};
 use std::collections::HashMap;
+use uuid::Uuid;
 
 use crate::middleware::auth::AuthenticatedUser;
+use crate::services::access::MEMBER_WORKSPACES;
 use crate::utils::response::ApiResponse;
 
 #[derive(Debug, Serialize)]
@@ -24,6 +26,23 @@ pub struct TaskStats {
     pub overdue_tasks: i64,
     pub due_today: i64,
     pub due_this_week: i64,
+
+    // Breakdown per project; tasks outside any project have no project_id
+    pub projects: Vec<ProjectStats>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ProjectStats {
+    pub project_id: Option<Uuid>,
+    pub project_name: Option<String>,
+    pub color: Option<String>,
+    pub total_tasks: i64,
+    pub todo: i64,
+    pub doing: i64,
+    pub done: i64,
+    pub overdue: i64,
+    #[sqlx(default)]
+    pub completion_rate: f64,
 }
 
 #[derive(Debug, Serialize)]
@@ -49,6 +68,7 @@ pub struct StatsQuery {
     pub period: Option<String>,        // day, week, month, year
     pub from_date: Option<String>,     // YYYY-MM-DD
     pub to_date: Option<String>,       // YYYY-MM-DD
+    pub workspace_id: Option<Uuid>,    // defaults to every workspace the user is in
 }
 
 #[get("/statistics")]
@@ -62,6 +82,12 @@ pub async fn get_task_statistics(
     let from_date = query.from_date.as_deref();
     let to_date = query.to_date.as_deref();
 
+    // Tasks of the workspaces the user is a member of, binding the user and the optional workspace
+    let scope = format!(
+        "workspace_id IN ({}$1) AND ($2::uuid IS NULL OR workspace_id = $2)",
+        MEMBER_WORKSPACES
+    );
+
     // Build date conditions
     let date_condition = match (from_date, to_date) {
         (Some(from), Some(to)) => format!("AND created_at >= '{}' AND created_at <= '{} 23:59:59'", from, to),
@@ -80,18 +106,20 @@ pub async fn get_task_statistics(
 
     // General statistics (without date filter)
     let total_tasks: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM tasks WHERE user_id = $1"
+        &format!("SELECT COUNT(*) FROM tasks WHERE {} AND deleted_at IS NULL", scope)
     )
     .bind(user_id)
+    .bind(query.workspace_id)
     .fetch_one(pool.get_ref())
     .await
     .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
 
     // Tasks by status
     let status_rows = sqlx::query(
-        "SELECT status::text as status, COUNT(*) as count FROM tasks WHERE user_id = $1 GROUP BY status"
+        &format!("SELECT status::text as status, COUNT(*) as count FROM tasks WHERE {} AND deleted_at IS NULL GROUP BY status", scope)
     )
     .bind(user_id)
+    .bind(query.workspace_id)
     .fetch_all(pool.get_ref())
     .await
     .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
@@ -105,9 +133,10 @@ pub async fn get_task_statistics(
 
     // Tasks by priority
     let priority_rows = sqlx::query(
-        "SELECT priority::text as priority, COUNT(*) as count FROM tasks WHERE user_id = $1 GROUP BY priority"
+        &format!("SELECT priority::text as priority, COUNT(*) as count FROM tasks WHERE {} AND deleted_at IS NULL GROUP BY priority", scope)
     )
     .bind(user_id)
+    .bind(query.workspace_id)
     .fetch_all(pool.get_ref())
     .await
     .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
@@ -129,31 +158,34 @@ pub async fn get_task_statistics(
 
     // Period-specific statistics
     let period_created_query = format!(
-        "SELECT COUNT(*) FROM tasks WHERE user_id = $1 {}",
-        date_condition
+        "SELECT COUNT(*) FROM tasks WHERE {} AND deleted_at IS NULL {}",
+        scope, date_condition
     );
     let period_created: i64 = sqlx::query_scalar(&period_created_query)
         .bind(user_id)
+        .bind(query.workspace_id)
         .fetch_one(pool.get_ref())
         .await
         .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
 
     let period_completed_query = format!(
-        "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND status = 'done' {}",
-        date_condition.replace("created_at", "updated_at")
+        "SELECT COUNT(*) FROM tasks WHERE {} AND deleted_at IS NULL AND status = 'done' {}",
+        scope, date_condition.replace("created_at", "updated_at")
     );
     let period_completed: i64 = sqlx::query_scalar(&period_completed_query)
         .bind(user_id)
+        .bind(query.workspace_id)
         .fetch_one(pool.get_ref())
         .await
         .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
 
     let period_updated_query = format!(
-        "SELECT COUNT(*) FROM tasks WHERE user_id = $1 {}",
-        date_condition.replace("created_at", "updated_at")
+        "SELECT COUNT(*) FROM tasks WHERE {} AND deleted_at IS NULL {}",
+        scope, date_condition.replace("created_at", "updated_at")
     );
     let period_updated: i64 = sqlx::query_scalar(&period_updated_query)
         .bind(user_id)
+        .bind(query.workspace_id)
         .fetch_one(pool.get_ref())
         .await
         .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
@@ -164,14 +196,15 @@ pub async fn get_task_statistics(
             DATE_TRUNC('{}', created_at) as period,
             COUNT(*) as created_count
          FROM tasks 
-         WHERE user_id = $1 {} 
+         WHERE {} AND deleted_at IS NULL {} 
          GROUP BY DATE_TRUNC('{}', created_at) 
          ORDER BY period",
-        date_trunc, date_condition, date_trunc
+        date_trunc, scope, date_condition, date_trunc
     );
 
     let time_series_rows = sqlx::query(&time_series_query)
         .bind(user_id)
+        .bind(query.workspace_id)
         .fetch_all(pool.get_ref())
         .await
         .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
@@ -193,14 +226,15 @@ pub async fn get_task_statistics(
             DATE_TRUNC('{}', updated_at) as period,
             COUNT(*) as completed_count
          FROM tasks 
-         WHERE user_id = $1 AND status = 'done' {} 
+         WHERE {} AND deleted_at IS NULL AND status = 'done' {} 
          GROUP BY DATE_TRUNC('{}', updated_at) 
          ORDER BY period",
-        date_trunc, date_condition.replace("created_at", "updated_at"), date_trunc
+        date_trunc, scope, date_condition.replace("created_at", "updated_at"), date_trunc
     );
 
     let completed_series_rows = sqlx::query(&completed_series_query)
         .bind(user_id)
+        .bind(query.workspace_id)
         .fetch_all(pool.get_ref())
         .await
         .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
@@ -220,29 +254,71 @@ pub async fn get_task_statistics(
 
     // Additional statistics
     let overdue_tasks: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND due_date < NOW() AND status != 'done'"
+        &format!("SELECT COUNT(*) FROM tasks WHERE {} AND deleted_at IS NULL AND due_date < NOW() AND status != 'done'", scope)
     )
     .bind(user_id)
+    .bind(query.workspace_id)
     .fetch_one(pool.get_ref())
     .await
     .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
 
     let due_today: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND DATE(due_date) = CURRENT_DATE AND status != 'done'"
+        &format!("SELECT COUNT(*) FROM tasks WHERE {} AND deleted_at IS NULL AND DATE(due_date) = CURRENT_DATE AND status != 'done'", scope)
     )
     .bind(user_id)
+    .bind(query.workspace_id)
     .fetch_one(pool.get_ref())
     .await
     .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
 
     let due_this_week: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND due_date >= CURRENT_DATE AND due_date < DATE_TRUNC('week', CURRENT_DATE) + INTERVAL '1 week' AND status != 'done'"
+        &format!("SELECT COUNT(*) FROM tasks WHERE {} AND deleted_at IS NULL AND due_date >= CURRENT_DATE AND due_date < DATE_TRUNC('week', CURRENT_DATE) + INTERVAL '1 week' AND status != 'done'", scope)
     )
     .bind(user_id)
+    .bind(query.workspace_id)
     .fetch_one(pool.get_ref())
     .await
     .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
 
+    // Per-project breakdown, active projects in their display order
+    let mut projects = sqlx::query_as::<_, ProjectStats>(&format!(
+        r#"
+        (SELECT p.id AS project_id, p.name AS project_name, p.color,
+            COUNT(t.id) AS total_tasks,
+            COUNT(t.id) FILTER (WHERE t.status = 'todo') AS todo,
+            COUNT(t.id) FILTER (WHERE t.status = 'doing') AS doing,
+            COUNT(t.id) FILTER (WHERE t.status = 'done') AS done,
+            COUNT(t.id) FILTER (WHERE t.due_date < NOW() AND t.status != 'done') AS overdue
+         FROM projects p
+         LEFT JOIN tasks t ON t.project_id = p.id AND t.deleted_at IS NULL
+         WHERE p.workspace_id IN ({}$1) AND ($2::uuid IS NULL OR p.workspace_id = $2) AND NOT p.archived
+         GROUP BY p.id
+         ORDER BY p.sort_order, p.created_at)
+        UNION ALL
+        SELECT NULL, NULL, NULL,
+            COUNT(*),
+            COUNT(*) FILTER (WHERE status = 'todo'),
+            COUNT(*) FILTER (WHERE status = 'doing'),
+            COUNT(*) FILTER (WHERE status = 'done'),
+            COUNT(*) FILTER (WHERE due_date < NOW() AND status != 'done')
+         FROM tasks
+         WHERE {} AND deleted_at IS NULL AND project_id IS NULL
+         HAVING COUNT(*) > 0
+        "#,
+        MEMBER_WORKSPACES, scope
+    ))
+    .bind(user_id)
+    .bind(query.workspace_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|_| actix_web::error::ErrorInternalServerError("Database error"))?;
+
+    for project in &mut projects {
+        if project.total_tasks > 0 {
+            project.completion_rate = (project.done as f64 / project.total_tasks as f64) * 100.0;
+        }
+    }
+
     let stats = TaskStats {
         total_tasks,
         tasks_by_status,
@@ -265,6 +341,7 @@ pub async fn get_task_statistics(
         overdue_tasks,
         due_today,
         due_this_week,
+        projects,
     };
 
     Ok(HttpResponse::Ok().json(ApiResponse::success(stats, "Statistics retrieved successfully")))
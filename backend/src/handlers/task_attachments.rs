@@ -0,0 +1,329 @@
+use actix_multipart::Multipart;
+use actix_web::http::header::{
+    self, Charset, ContentDisposition, DispositionParam, DispositionType, ExtendedValue,
+};
+use actix_web::http::StatusCode;
+use actix_web::{web, get, post, delete, HttpRequest, HttpResponse, Result};
+use bytes::BytesMut;
+use futures_util::StreamExt;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{TaskAttachment, WorkspaceRole};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::access::{self, Permission};
+use crate::services::attachments::{self, AttachmentLimits};
+use crate::services::storage::{StorageBackend, StorageError};
+use crate::utils::byte_range::{parse_range, RangeRequest};
+use crate::utils::content_type;
+use crate::utils::response::*;
+
+// Multipart field carrying the file
+const FILE_FIELD: &str = "file";
+const MAX_FILENAME_CHARS: usize = 255;
+
+async fn fetch_attachment(pool: &PgPool, task_id: Uuid, attachment_id: Uuid) -> Result<Option<TaskAttachment>, sqlx::Error> {
+    sqlx::query_as::<_, TaskAttachment>("SELECT * FROM task_attachments WHERE id = $1 AND task_id = $2")
+        .bind(attachment_id)
+        .bind(task_id)
+        .fetch_optional(pool)
+        .await
+}
+
+// Keeps the last path component of a client-supplied name, without control characters
+fn clean_filename(filename: Option<&str>) -> String {
+    let name = filename
+        .and_then(|name| name.rsplit(['/', '\\']).next())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILENAME_CHARS)
+        .collect::<String>();
+
+    match name.trim() {
+        "" | "." | ".." => "file".to_string(),
+        name => name.to_string(),
+    }
+}
+
+#[get("/{id}/attachments")]
+pub async fn get_attachments(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
+    let attachments = sqlx::query_as::<_, TaskAttachment>(
+        "SELECT * FROM task_attachments WHERE task_id = $1 ORDER BY created_at ASC, id ASC"
+    )
+    .bind(task_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match attachments {
+        Ok(attachments) => Ok(ok_response(attachments, "Attachments retrieved successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to fetch attachments: {}", e))),
+    }
+}
+
+#[post("/{id}/attachments")]
+pub async fn upload_attachment(
+    pool: web::Data<PgPool>,
+    storage: web::Data<dyn StorageBackend>,
+    limits: web::Data<AttachmentLimits>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    mut payload: Multipart,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Write).await {
+        return Ok(e.into_response("task"));
+    }
+
+    // The first `file` field is the upload; other fields are ignored
+    let mut upload = None;
+    while let Some(field) = payload.next().await {
+        let mut field = match field {
+            Ok(field) => field,
+            Err(e) => return Ok(bad_request_response(ApiError::new("INVALID_MULTIPART", &e.to_string()))),
+        };
+        if upload.is_some() || field.name() != Some(FILE_FIELD) {
+            continue;
+        }
+
+        let filename = clean_filename(field.content_disposition().and_then(|cd| cd.get_filename()));
+        let declared_type = field.content_type().map(|mime| mime.essence_str().to_string());
+
+        let mut data = BytesMut::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => return Ok(bad_request_response(ApiError::new("INVALID_MULTIPART", &e.to_string()))),
+            };
+            if (data.len() + chunk.len()) as i64 > limits.max_file_bytes {
+                return Ok(HttpResponse::PayloadTooLarge().json(ApiError::with_fields(
+                    "FILE_TOO_LARGE",
+                    "File exceeds the maximum attachment size",
+                    json!({ "max_file_bytes": limits.max_file_bytes }),
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        upload = Some((filename, declared_type, data.freeze()));
+    }
+
+    let Some((filename, declared_type, data)) = upload else {
+        let error = ApiError::new("MISSING_FILE", "Expected a multipart field named 'file'");
+        return Ok(bad_request_response(error));
+    };
+
+    if data.is_empty() {
+        return Ok(bad_request_response(ApiError::new("EMPTY_FILE", "File is empty")));
+    }
+
+    let content_type = match content_type::resolve(&data, declared_type.as_deref()) {
+        Ok(content_type) => content_type,
+        Err(message) => {
+            return Ok(HttpResponse::UnsupportedMediaType().json(ApiError::new("UNSUPPORTED_FILE_TYPE", &message)));
+        }
+    };
+
+    let size = data.len() as i64;
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    // Serialize a user's uploads so concurrent ones can't overrun the quota together
+    let used = sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1::text))")
+        .bind(user.0.id)
+        .execute(&mut *tx)
+        .await;
+    let used = match used {
+        Ok(_) => sqlx::query_scalar::<_, i64>(
+            "SELECT COALESCE(SUM(size_bytes), 0)::BIGINT FROM task_attachments WHERE uploaded_by = $1"
+        )
+        .bind(user.0.id)
+        .fetch_one(&mut *tx)
+        .await,
+        Err(e) => Err(e),
+    };
+    let used = match used {
+        Ok(used) => used,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to upload attachment: {}", e))),
+    };
+
+    if used + size > limits.user_quota_bytes {
+        return Ok(HttpResponse::PayloadTooLarge().json(ApiError::with_fields(
+            "QUOTA_EXCEEDED",
+            "Attachment storage quota exceeded",
+            json!({ "used_bytes": used, "quota_bytes": limits.user_quota_bytes }),
+        )));
+    }
+
+    let attachment_id = Uuid::new_v4();
+    let storage_key = attachments::storage_key(task_id, attachment_id);
+
+    let attachment = sqlx::query_as::<_, TaskAttachment>(
+        r#"
+        INSERT INTO task_attachments (id, task_id, uploaded_by, filename, content_type, size_bytes, storage_key)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#
+    )
+    .bind(attachment_id)
+    .bind(task_id)
+    .bind(user.0.id)
+    .bind(&filename)
+    .bind(&content_type)
+    .bind(size)
+    .bind(&storage_key)
+    .fetch_one(&mut *tx)
+    .await;
+
+    let attachment = match attachment {
+        Ok(attachment) => attachment,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to upload attachment: {}", e))),
+    };
+
+    if let Err(e) = storage.put(&storage_key, data, &content_type).await {
+        return Ok(internal_error_response(&format!("Failed to store attachment: {}", e)));
+    }
+
+    if let Err(e) = tx.commit().await {
+        let _ = storage.delete(&storage_key).await;
+        return Ok(internal_error_response(&format!("Failed to upload attachment: {}", e)));
+    }
+
+    Ok(created_response(attachment, "Attachment uploaded successfully"))
+}
+
+#[get("/{id}/attachments/{attachment_id}")]
+pub async fn download_attachment(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    storage: web::Data<dyn StorageBackend>,
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse> {
+    let (task_id, attachment_id) = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
+    let attachment = match fetch_attachment(pool.get_ref(), task_id, attachment_id).await {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => return Ok(not_found_response("attachment")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let size = attachment.size_bytes as u64;
+    let range = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(RangeRequest::Full, |value| parse_range(value, size));
+
+    let range = match range {
+        RangeRequest::Full => None,
+        RangeRequest::Partial(range) => Some(range),
+        RangeRequest::Unsatisfiable => {
+            return Ok(HttpResponse::RangeNotSatisfiable()
+                .insert_header((header::CONTENT_RANGE, format!("bytes */{}", size)))
+                .json(ApiError::new("RANGE_NOT_SATISFIABLE", "Requested range is outside the file")));
+        }
+    };
+
+    let stream = match storage.get(&attachment.storage_key, range).await {
+        Ok(stream) => stream,
+        Err(StorageError::NotFound) => return Ok(not_found_response("attachment")),
+        Err(e) => return Ok(internal_error_response(&format!("Failed to read attachment: {}", e))),
+    };
+
+    // Non-ASCII names go in filename*, with an ASCII fallback for old clients
+    let mut disposition = vec![DispositionParam::Filename(
+        attachment.filename.chars().map(|c| if c.is_ascii() && c != '"' { c } else { '_' }).collect(),
+    )];
+    if !attachment.filename.is_ascii() {
+        disposition.push(DispositionParam::FilenameExt(ExtendedValue {
+            charset: Charset::Ext("UTF-8".to_string()),
+            language_tag: None,
+            value: attachment.filename.clone().into_bytes(),
+        }));
+    }
+
+    let mut response = HttpResponse::build(if range.is_some() { StatusCode::PARTIAL_CONTENT } else { StatusCode::OK });
+    response
+        .insert_header((header::CONTENT_TYPE, attachment.content_type.as_str()))
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: disposition,
+        });
+
+    match range {
+        Some(range) => {
+            response
+                .insert_header((header::CONTENT_RANGE, format!("bytes {}-{}/{}", range.start, range.end, size)))
+                .no_chunking(range.length());
+        }
+        None => {
+            response.no_chunking(size);
+        }
+    }
+
+    Ok(response.streaming(stream))
+}
+
+#[delete("/{id}/attachments/{attachment_id}")]
+pub async fn delete_attachment(
+    pool: web::Data<PgPool>,
+    storage: web::Data<dyn StorageBackend>,
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse> {
+    let (task_id, attachment_id) = path.into_inner();
+
+    let role = match access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        Ok(access) => access.role,
+        Err(e) => return Ok(e.into_response("task")),
+    };
+
+    let attachment = match fetch_attachment(pool.get_ref(), task_id, attachment_id).await {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => return Ok(not_found_response("attachment")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    // Uploaders remove their own files; admins can remove any
+    if attachment.uploaded_by != Some(user.0.id) && role < WorkspaceRole::Admin {
+        return Ok(forbidden_response("Only the uploader or a workspace admin can delete an attachment"));
+    }
+
+    let result = sqlx::query("DELETE FROM task_attachments WHERE id = $1")
+        .bind(attachment_id)
+        .execute(pool.get_ref())
+        .await;
+
+    if let Err(e) = result {
+        return Ok(internal_error_response(&format!("Failed to delete attachment: {}", e)));
+    }
+
+    if let Err(e) = attachments::purge_deleted_blobs(pool.get_ref(), storage.get_ref()).await {
+        tracing::error!("Failed to purge attachment blobs: {}", e);
+    }
+
+    let success_data = json!({"deleted": true});
+    Ok(ok_response(success_data, "Attachment deleted successfully"))
+}
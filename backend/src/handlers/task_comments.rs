@@ -0,0 +1,311 @@
+use actix_web::{web, get, post, put, delete, HttpResponse, Result};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{
+    Task, TaskComment, TaskCommentEdit, CreateCommentRequest, UpdateCommentRequest, CommentQuery, CommentsPage,
+    WorkspaceRole,
+};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::access::{self, Permission};
+use crate::services::mentions;
+use crate::services::notification_service::NotificationService;
+use crate::utils::cursor;
+use crate::utils::response::*;
+
+// Base SELECT for comment reads, including the author's name
+const COMMENT_SELECT: &str = r#"
+    SELECT c.id, c.task_id, c.user_id, COALESCE(u.name, 'Deleted user') AS author_name, c.reply_to, c.body,
+        c.edited_at, c.created_at, c.updated_at
+    FROM task_comments c
+    LEFT JOIN users u ON u.id = c.user_id"#;
+
+async fn fetch_comment(pool: &PgPool, task_id: Uuid, comment_id: Uuid) -> Result<Option<TaskComment>, sqlx::Error> {
+    sqlx::query_as::<_, TaskComment>(&format!("{} WHERE c.id = $1 AND c.task_id = $2", COMMENT_SELECT))
+        .bind(comment_id)
+        .bind(task_id)
+        .fetch_optional(pool)
+        .await
+}
+
+#[get("/{id}/comments")]
+pub async fn get_comments(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    query: web::Query<CommentQuery>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let position = match cursor::position::<Uuid>(query.cursor.as_deref()) {
+        Ok(position) => position,
+        Err(message) => return Ok(bad_request_response(ApiError::new("INVALID_CURSOR", &message))),
+    };
+
+    let comments = sqlx::query_as::<_, TaskComment>(
+        &format!(
+            r#"{}
+            WHERE c.task_id = $1
+              AND ($2::timestamptz IS NULL OR (c.created_at, c.id) > ($2, $3))
+            ORDER BY c.created_at ASC, c.id ASC
+            LIMIT $4
+            "#,
+            COMMENT_SELECT
+        )
+    )
+    .bind(task_id)
+    .bind(position.as_ref().map(|p| p.at))
+    .bind(position.as_ref().map(|p| p.id))
+    .bind(limit + 1)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let mut comments = match comments {
+        Ok(comments) => comments,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to fetch comments: {}", e))),
+    };
+
+    let next_cursor = cursor::next_page(&mut comments, limit, |last| Some((last.created_at, last.id)));
+
+    Ok(ok_response(CommentsPage { comments, next_cursor }, "Comments retrieved successfully"))
+}
+
+#[post("/{id}/comments")]
+pub async fn create_comment(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    comment_data: web::Json<CreateCommentRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = comment_data.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let task_id = path.into_inner();
+    let comment_data = comment_data.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Write).await {
+        return Ok(e.into_response("task"));
+    }
+
+    // Replies stay within the task they belong to
+    if let Some(reply_to) = comment_data.reply_to {
+        match fetch_comment(pool.get_ref(), task_id, reply_to).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                let error = ApiError::new("INVALID_REPLY", "Comment to reply to not found");
+                return Ok(bad_request_response(error));
+            }
+            Err(e) => return Ok(internal_error_response(&e.to_string())),
+        }
+    }
+
+    let comment_id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO task_comments (task_id, user_id, reply_to, body) VALUES ($1, $2, $3, $4) RETURNING id"
+    )
+    .bind(task_id)
+    .bind(user.0.id)
+    .bind(comment_data.reply_to)
+    .bind(&comment_data.body)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    let comment_id = match comment_id {
+        Ok(comment_id) => comment_id,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to create comment: {}", e))),
+    };
+
+    let task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = $1")
+        .bind(task_id)
+        .fetch_one(pool.get_ref())
+        .await;
+
+    // The owner and assignee follow the discussion, except for their own comments
+    if let Ok(task) = task {
+        let mut recipients = vec![task.user_id];
+        recipients.extend(task.assignee_id);
+        recipients.dedup();
+        for recipient in recipients.into_iter().filter(|&id| id != user.0.id) {
+            let _ = notification_service.notify_task_commented(
+                recipient,
+                &task.title,
+                task.id,
+                comment_id,
+                &user.0.name,
+            ).await;
+        }
+    }
+
+    if let Err(e) = mentions::sync_mentions(
+        pool.get_ref(),
+        &notification_service,
+        task_id,
+        Some(comment_id),
+        &comment_data.body,
+        &user.0,
+    ).await {
+        tracing::error!("Failed to record mentions for comment {}: {}", comment_id, e);
+    }
+
+    match fetch_comment(pool.get_ref(), task_id, comment_id).await {
+        Ok(Some(comment)) => Ok(created_response(comment, "Comment created successfully")),
+        Ok(None) => Ok(not_found_response("comment")),
+        Err(e) => Ok(internal_error_response(&e.to_string())),
+    }
+}
+
+#[put("/{id}/comments/{comment_id}")]
+pub async fn update_comment(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+    comment_data: web::Json<UpdateCommentRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = comment_data.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let (task_id, comment_id) = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
+    let comment = match fetch_comment(pool.get_ref(), task_id, comment_id).await {
+        Ok(Some(comment)) => comment,
+        Ok(None) => return Ok(not_found_response("comment")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    if comment.user_id != Some(user.0.id) {
+        return Ok(forbidden_response("Only the author can edit a comment"));
+    }
+
+    if comment.body == comment_data.body {
+        return Ok(ok_response(comment, "Comment updated successfully"));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    // Keep the version being replaced
+    let result = sqlx::query("INSERT INTO task_comment_edits (comment_id, body) VALUES ($1, $2)")
+        .bind(comment_id)
+        .bind(&comment.body)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = result {
+        return Ok(internal_error_response(&format!("Failed to update comment: {}", e)));
+    }
+
+    let result = sqlx::query("UPDATE task_comments SET body = $2, edited_at = NOW() WHERE id = $1")
+        .bind(comment_id)
+        .bind(&comment_data.body)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = result {
+        return Ok(internal_error_response(&format!("Failed to update comment: {}", e)));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to update comment: {}", e)));
+    }
+
+    if let Err(e) = mentions::sync_mentions(
+        pool.get_ref(),
+        &notification_service,
+        task_id,
+        Some(comment_id),
+        &comment_data.body,
+        &user.0,
+    ).await {
+        tracing::error!("Failed to record mentions for comment {}: {}", comment_id, e);
+    }
+
+    match fetch_comment(pool.get_ref(), task_id, comment_id).await {
+        Ok(Some(comment)) => Ok(ok_response(comment, "Comment updated successfully")),
+        Ok(None) => Ok(not_found_response("comment")),
+        Err(e) => Ok(internal_error_response(&e.to_string())),
+    }
+}
+
+#[delete("/{id}/comments/{comment_id}")]
+pub async fn delete_comment(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse> {
+    let (task_id, comment_id) = path.into_inner();
+
+    let role = match access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        Ok(access) => access.role,
+        Err(e) => return Ok(e.into_response("task")),
+    };
+
+    let comment = match fetch_comment(pool.get_ref(), task_id, comment_id).await {
+        Ok(Some(comment)) => comment,
+        Ok(None) => return Ok(not_found_response("comment")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    // Authors remove their own comments; admins can moderate any
+    if comment.user_id != Some(user.0.id) && role < WorkspaceRole::Admin {
+        return Ok(forbidden_response("Only the author or a workspace admin can delete a comment"));
+    }
+
+    let result = sqlx::query("DELETE FROM task_comments WHERE id = $1")
+        .bind(comment_id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(_) => {
+            let success_data = serde_json::json!({"deleted": true});
+            Ok(ok_response(success_data, "Comment deleted successfully"))
+        }
+        Err(e) => Ok(internal_error_response(&format!("Failed to delete comment: {}", e))),
+    }
+}
+
+#[get("/{id}/comments/{comment_id}/history")]
+pub async fn get_comment_history(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse> {
+    let (task_id, comment_id) = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
+    match fetch_comment(pool.get_ref(), task_id, comment_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Ok(not_found_response("comment")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    }
+
+    let edits = sqlx::query_as::<_, TaskCommentEdit>(
+        "SELECT * FROM task_comment_edits WHERE comment_id = $1 ORDER BY edited_at ASC, id ASC"
+    )
+    .bind(comment_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match edits {
+        Ok(edits) => Ok(ok_response(edits, "Comment history retrieved successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to fetch comment history: {}", e))),
+    }
+}
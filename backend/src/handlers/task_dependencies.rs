@@ -0,0 +1,143 @@
+use actix_web::{web, get, post, delete, HttpResponse, Result};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::task_query::TASK_SELECT;
+use crate::handlers::tasks::task_in_workspace;
+use crate::models::{Task, TaskDependency, AddDependencyRequest, TaskDependenciesResponse};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::access::{self, Permission};
+use crate::services::task_dependencies::would_create_cycle;
+use crate::utils::response::*;
+
+#[get("/{id}/dependencies")]
+pub async fn get_dependencies(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
+    let blocked_by = sqlx::query_as::<_, Task>(
+        &format!(
+            "{} WHERE id IN (SELECT blocked_by_id FROM task_dependencies WHERE task_id = $1) AND deleted_at IS NULL ORDER BY created_at ASC",
+            TASK_SELECT
+        )
+    )
+    .bind(task_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let blocking = sqlx::query_as::<_, Task>(
+        &format!(
+            "{} WHERE id IN (SELECT task_id FROM task_dependencies WHERE blocked_by_id = $1) AND deleted_at IS NULL ORDER BY created_at ASC",
+            TASK_SELECT
+        )
+    )
+    .bind(task_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match (blocked_by, blocking) {
+        (Ok(blocked_by), Ok(blocking)) => {
+            let response = TaskDependenciesResponse { blocked_by, blocking };
+            Ok(ok_response(response, "Dependencies retrieved successfully"))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            Ok(internal_error_response(&format!("Failed to fetch dependencies: {}", e)))
+        }
+    }
+}
+
+#[post("/{id}/dependencies")]
+pub async fn add_dependency(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<AddDependencyRequest>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+    let blocked_by_id = body.blocked_by_id;
+
+    let workspace_id = match access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Write).await {
+        Ok(access) => access.workspace_id,
+        Err(e) => return Ok(e.into_response("task")),
+    };
+
+    // Blockers must come from the same workspace
+    match task_in_workspace(pool.get_ref(), blocked_by_id, workspace_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            let error = ApiError::new("INVALID_DEPENDENCY", "Blocking task not found");
+            return Ok(bad_request_response(error));
+        }
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    }
+
+    match would_create_cycle(pool.get_ref(), task_id, blocked_by_id).await {
+        Ok(false) => {}
+        Ok(true) => {
+            let error = ApiError::new("DEPENDENCY_CYCLE", "Dependency would create a cycle");
+            return Ok(bad_request_response(error));
+        }
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    }
+
+    let dependency = sqlx::query_as::<_, TaskDependency>(
+        r#"
+        INSERT INTO task_dependencies (task_id, blocked_by_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        RETURNING *
+        "#
+    )
+    .bind(task_id)
+    .bind(blocked_by_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match dependency {
+        Ok(Some(dependency)) => Ok(created_response(dependency, "Dependency added successfully")),
+        Ok(None) => Ok(conflict_response("Dependency already exists")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to add dependency: {}", e))),
+    }
+}
+
+#[delete("/{id}/dependencies/{blocked_by_id}")]
+pub async fn remove_dependency(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse> {
+    let (task_id, blocked_by_id) = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Write).await {
+        return Ok(e.into_response("task"));
+    }
+
+    let result = sqlx::query(
+        "DELETE FROM task_dependencies WHERE task_id = $1 AND blocked_by_id = $2"
+    )
+    .bind(task_id)
+    .bind(blocked_by_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(rows) => {
+            if rows.rows_affected() == 0 {
+                Ok(not_found_response("dependency"))
+            } else {
+                let success_data = serde_json::json!({"deleted": true});
+                Ok(ok_response(success_data, "Dependency removed successfully"))
+            }
+        }
+        Err(e) => {
+            Ok(internal_error_response(&format!("Failed to remove dependency: {}", e)))
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use actix_web::{web, get, HttpResponse, Result};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::models::{TaskEvent, TaskEventQuery, TaskEventsPage};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::access::{self, Permission, MEMBER_WORKSPACES};
+use crate::utils::cursor;
+use crate::utils::response::*;
+
+// Base SELECT for event reads, including the actor's name
+const EVENT_SELECT: &str = r#"
+    SELECT e.id, e.task_id, e.workspace_id, e.actor_id, u.name AS actor_name, e.event_type, e.task_title,
+        e.changes, e.created_at
+    FROM task_events e
+    LEFT JOIN users u ON u.id = e.actor_id"#;
+
+// Which events a listing covers
+enum EventScope {
+    Task(Uuid),
+    // Everything in the workspaces the user belongs to
+    Member(Uuid),
+}
+
+// Newest-first keyset page of events in `scope`, filtered by `query`
+async fn events_page(pool: &PgPool, scope: EventScope, query: &TaskEventQuery) -> HttpResponse {
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let position = match cursor::position::<Uuid>(query.cursor.as_deref()) {
+        Ok(position) => position,
+        Err(message) => return bad_request_response(ApiError::new("INVALID_CURSOR", &message)),
+    };
+
+    let mut builder = QueryBuilder::<Postgres>::new(EVENT_SELECT);
+    match scope {
+        EventScope::Task(task_id) => {
+            builder.push(" WHERE e.task_id = ").push_bind(task_id);
+        }
+        EventScope::Member(user_id) => {
+            builder
+                .push(format!(" WHERE e.workspace_id IN ({}", MEMBER_WORKSPACES))
+                .push_bind(user_id)
+                .push(")");
+        }
+    }
+
+    if let Some(workspace_id) = query.workspace_id {
+        builder.push(" AND e.workspace_id = ").push_bind(workspace_id);
+    }
+    if let Some(event_types) = &query.event_type {
+        builder.push(" AND e.event_type IN (");
+        let mut list = builder.separated(", ");
+        for event_type in &event_types.0 {
+            list.push_bind(*event_type);
+        }
+        list.push_unseparated(")");
+    }
+    if let Some(from) = query.from {
+        builder.push(" AND e.created_at >= ").push_bind(from);
+    }
+    if let Some(to) = query.to {
+        builder.push(" AND e.created_at < ").push_bind(to);
+    }
+    if let Some(position) = &position {
+        builder
+            .push(" AND (e.created_at, e.id) < (")
+            .push_bind(position.at)
+            .push(", ")
+            .push_bind(position.id)
+            .push(")");
+    }
+    builder
+        .push(" ORDER BY e.created_at DESC, e.id DESC LIMIT ")
+        .push_bind(limit + 1);
+
+    let mut events = match builder.build_query_as::<TaskEvent>().fetch_all(pool).await {
+        Ok(events) => events,
+        Err(e) => return internal_error_response(&format!("Failed to fetch task history: {}", e)),
+    };
+
+    let next_cursor = cursor::next_page(&mut events, limit, |last| Some((last.created_at, last.id)));
+
+    ok_response(TaskEventsPage { events, next_cursor }, "Task history retrieved successfully")
+}
+
+#[get("/{id}/history")]
+pub async fn get_task_history(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    query: web::Query<TaskEventQuery>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
+    Ok(events_page(pool.get_ref(), EventScope::Task(task_id), &query).await)
+}
+
+#[get("")]
+pub async fn get_activity(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<TaskEventQuery>,
+) -> Result<HttpResponse> {
+    Ok(events_page(pool.get_ref(), EventScope::Member(user.0.id), &query).await)
+}
@@ -1,13 +1,34 @@
-use actix_web::{web, get, post, put, delete, HttpResponse, Result};
+use actix_web::{web, get, post, put, patch, delete, http::header, HttpRequest, HttpResponse, Result};
 use serde_json::json;
-use sqlx::PgPool;
+use sqlx::{Acquire, PgConnection, PgPool, Postgres, QueryBuilder};
+use std::collections::{HashMap, HashSet};
 use validator::Validate;
 use uuid::Uuid;
 
-use crate::models::{Task, CreateTaskRequest, UpdateTaskRequest, TaskFilters, TasksResponse};
+use crate::models::{
+    Task, TaskStatus, CreateTaskRequest, UpdateTaskRequest, PatchTaskRequest, TaskFilters, TasksResponse, TaskHighlight, TaskTreeNode,
+    ChildrenOnDelete, DeleteTaskQuery, AssignTaskRequest, TaskEventType, TrashQuery, TrashPage,
+    MoveTaskRequest, BulkOperation, BulkMode, BulkTaskRequest, BulkTaskResponse, BulkItemStatus, BulkItemResult, BulkItemError,
+};
+use crate::database::task_query::{TaskQuery, TASK_SELECT};
+use crate::handlers::projects::project_in_workspace;
 use crate::middleware::auth::AuthenticatedUser;
+use crate::utils::cursor;
+use crate::utils::etag;
+use crate::utils::recurrence::RecurrenceRule;
 use crate::utils::response::*;
+use crate::services::access::{self, AccessError, Permission, MEMBER_WORKSPACES};
+use crate::services::attachments;
+use crate::services::mentions;
 use crate::services::notification_service::NotificationService;
+use crate::services::storage::StorageBackend;
+use crate::services::task_dependencies;
+use crate::services::task_events;
+use crate::services::task_position;
+use crate::services::task_recurrence;
+use crate::services::task_tree::{self, CompletedParent, MAX_TASK_DEPTH};
+use crate::services::trash;
+use crate::services::workspaces;
 
 #[get("")]
 pub async fn get_tasks(
@@ -16,169 +37,54 @@ pub async fn get_tasks(
     query: web::Query<TaskFilters>,
 ) -> Result<HttpResponse> {
     let filters = query.into_inner();
-    let page = filters.page.unwrap_or(1);
-    let limit = filters.limit.unwrap_or(10).min(100);
+    let page = filters.page.unwrap_or(1).max(1);
+    let limit = filters.limit.unwrap_or(10).clamp(1, 100);
     let offset = (page - 1) * limit;
 
-    // Build dynamic query with filters
-    let mut where_conditions = vec!["user_id = $1".to_string()];
-    let mut params: Vec<String> = vec![];
-    let mut param_count = 2;
-
-    // Add status filter
-    if let Some(status) = &filters.status {
-        where_conditions.push(format!("status = ${}", param_count));
-        params.push(format!("{:?}", status).to_lowercase().trim_matches('"').to_string());
-        param_count += 1;
-    }
-
-    // Add priority filter
-    if let Some(priority) = &filters.priority {
-        where_conditions.push(format!("priority = ${}", param_count));
-        params.push(format!("{:?}", priority).to_lowercase().trim_matches('"').to_string());
-        param_count += 1;
-    }
-
-    // Add search filter
-    let search_pattern = if let Some(search) = &filters.search {
-        where_conditions.push(format!("(title ILIKE ${} OR description ILIKE ${})", param_count, param_count));
-        let pattern = format!("%{}%", search);
-        params.push(pattern.clone());
-        param_count += 1;
-        Some(pattern)
-    } else {
-        None
-    };
-
-    // Add tag filter
-    if let Some(tag) = &filters.tag {
-        where_conditions.push(format!("tags::text ILIKE ${}", param_count));
-        params.push(format!("%{}%", tag));
-        param_count += 1;
-    }
-
-    // Add created date range filters
-    if let Some(created_from) = &filters.created_from {
-        where_conditions.push(format!("created_at >= ${}", param_count));
-        params.push(created_from.to_rfc3339());
-        param_count += 1;
-    }
-
-    if let Some(created_to) = &filters.created_to {
-        where_conditions.push(format!("created_at <= ${}", param_count));
-        params.push(created_to.to_rfc3339());
-        param_count += 1;
-    }
-
-    // Add due date range filters
-    if let Some(due_from) = &filters.due_from {
-        where_conditions.push(format!("due_date >= ${}", param_count));
-        params.push(due_from.to_rfc3339());
-        param_count += 1;
-    }
-
-    if let Some(due_to) = &filters.due_to {
-        where_conditions.push(format!("due_date <= ${}", param_count));
-        params.push(due_to.to_rfc3339());
-        param_count += 1;
-    }
-
-    let where_clause = where_conditions.join(" AND ");
-    
-    // Build ORDER BY clause
-    let sort_field = match filters.sort_by.as_deref() {
-        Some("title") => "title",
-        Some("status") => "status",
-        Some("priority") => "priority", 
-        Some("due_date") => "due_date",
-        Some("updated_at") => "updated_at",
-        _ => "created_at", // default
-    };
-    
-    let sort_order = match filters.sort_order.as_deref() {
-        Some("asc") => "ASC",
-        _ => "DESC", // default
+    let task_query = match TaskQuery::new(user.0.id, &filters) {
+        Ok(task_query) => task_query,
+        Err(message) => {
+            return Ok(bad_request_response(ApiError::new("INVALID_CURSOR", &message)));
+        }
     };
-    
-    let query_str = format!(
-        "SELECT * FROM tasks WHERE {} ORDER BY {} {} LIMIT ${} OFFSET ${}",
-        where_clause, sort_field, sort_order, param_count, param_count + 1
-    );
-
-    // Build the query with all parameters
-    let mut query = sqlx::query_as::<_, Task>(&query_str).bind(&user.0.id);
-
-    // Bind all filter parameters in order
-    if let Some(status) = &filters.status {
-        query = query.bind(status);
-    }
-    if let Some(priority) = &filters.priority {
-        query = query.bind(priority);
-    }
-    if let Some(search_pattern) = &search_pattern {
-        query = query.bind(search_pattern);
-    }
-    if let Some(tag) = &filters.tag {
-        query = query.bind(format!("%{}%", tag));
-    }
-    if let Some(created_from) = &filters.created_from {
-        query = query.bind(created_from);
-    }
-    if let Some(created_to) = &filters.created_to {
-        query = query.bind(created_to);
-    }
-    if let Some(due_from) = &filters.due_from {
-        query = query.bind(due_from);
-    }
-    if let Some(due_to) = &filters.due_to {
-        query = query.bind(due_to);
-    }
-
-    // Bind limit and offset
-    query = query.bind(limit as i64).bind(offset as i64);
-
-    let tasks = query.fetch_all(pool.get_ref()).await;
 
-    let tasks = match tasks {
-        Ok(tasks) => tasks,
+    let tasks_page = match task_query.fetch_page(pool.get_ref(), limit, offset).await {
+        Ok(tasks_page) => tasks_page,
         Err(e) => {
             return Ok(internal_error_response(&format!("Failed to fetch tasks: {}", e)));
         }
     };
+    let tasks = tasks_page.tasks;
 
-    // Get total count with same filters
-    let count_query_str = format!("SELECT COUNT(*) FROM tasks WHERE {}", where_clause);
-    let mut count_query = sqlx::query_scalar::<_, i64>(&count_query_str).bind(&user.0.id);
-
-    // Bind the same filter parameters for count query
-    if let Some(status) = &filters.status {
-        count_query = count_query.bind(status);
-    }
-    if let Some(priority) = &filters.priority {
-        count_query = count_query.bind(priority);
-    }
-    if let Some(search_pattern) = &search_pattern {
-        count_query = count_query.bind(search_pattern);
-    }
-    if let Some(tag) = &filters.tag {
-        count_query = count_query.bind(format!("%{}%", tag));
-    }
-    if let Some(created_from) = &filters.created_from {
-        count_query = count_query.bind(created_from);
-    }
-    if let Some(created_to) = &filters.created_to {
-        count_query = count_query.bind(created_to);
-    }
-    if let Some(due_from) = &filters.due_from {
-        count_query = count_query.bind(due_from);
-    }
-    if let Some(due_to) = &filters.due_to {
-        count_query = count_query.bind(due_to);
-    }
+    // Infinite-scroll clients can skip the count query
+    let total_count = if filters.include_total == Some(false) {
+        None
+    } else {
+        Some(
+            task_query
+                .count_query()
+                .build_query_scalar::<i64>()
+                .fetch_one(pool.get_ref())
+                .await
+                .unwrap_or(0),
+        )
+    };
 
-    let total_count = count_query.fetch_one(pool.get_ref()).await.unwrap_or(0);
+    let total_pages = total_count.map(|total| ((total as f64) / (limit as f64)).ceil() as u32);
 
-    let total_pages = ((total_count as f64) / (limit as f64)).ceil() as u32;
+    // Highlighted snippets when searching
+    let task_ids: Vec<Uuid> = tasks.iter().map(|task| task.id).collect();
+    let highlights = match task_query.highlights_query(&task_ids) {
+        Some(mut highlights_query) => {
+            match highlights_query.build_query_as::<TaskHighlight>().fetch_all(pool.get_ref()).await {
+                Ok(highlights) => Some(highlights),
+                Err(e) => {
+                    return Ok(internal_error_response(&format!("Failed to build search highlights: {}", e)));
+                }
+            }
+        }
+        None => None,
+    };
 
     let response = TasksResponse {
         tasks,
@@ -186,6 +92,8 @@ pub async fn get_tasks(
         page,
         limit,
         total_pages,
+        next_cursor: tasks_page.next_cursor,
+        highlights,
     };
 
     Ok(ok_response(response, "Tasks retrieved successfully"))
@@ -211,14 +119,62 @@ pub async fn create_task(
             return Ok(bad_request_response(error));
         }
     }
+
+    let workspace_id = match task_data.workspace_id {
+        Some(workspace_id) => workspace_id,
+        None => match workspaces::personal_workspace(pool.get_ref(), user.0.id).await {
+            Ok(workspace_id) => workspace_id,
+            Err(e) => return Ok(internal_error_response(&e.to_string())),
+        },
+    };
+
+    if let Err(e) = access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Write).await {
+        return Ok(e.into_response("workspace"));
+    }
+
+    if let Some(parent_id) = task_data.parent_id {
+        if let Err(response) = validate_parent(pool.get_ref(), workspace_id, parent_id, None).await {
+            return Ok(response);
+        }
+    }
+
+    if let Some(project_id) = task_data.project_id {
+        if let Err(response) = validate_project(pool.get_ref(), workspace_id, project_id).await {
+            return Ok(response);
+        }
+    }
+
+    if let Some(assignee_id) = task_data.assignee_id {
+        if let Err(response) = validate_assignee(pool.get_ref(), workspace_id, assignee_id).await {
+            return Ok(response);
+        }
+    }
+
+    let recurrence_rule = match parse_recurrence(task_data.recurrence.as_deref()) {
+        Ok(rule) => rule,
+        Err(message) => {
+            let error = ApiError::new("INVALID_RECURRENCE", &message);
+            return Ok(bad_request_response(error));
+        }
+    };
     
     let task_id = Uuid::new_v4();
+    // A recurring task starts its own series as instance 1
+    let recurrence_series = recurrence_rule.as_ref().map(|_| (task_id, 1));
     let tags_json = task_data.tags.map(|tags| json!(tags)).unwrap_or_else(|| json!([]));
 
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
     let result = sqlx::query(
         r#"
-        INSERT INTO tasks (id, user_id, title, description, status, priority, due_date, tags)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        INSERT INTO tasks (
+            id, user_id, title, description, status, priority, due_date, tags, parent_id,
+            project_id, recurrence_rule, recurrence_series_id, recurrence_index, workspace_id, assignee_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
         "#
     )
     .bind(&task_id)
@@ -229,50 +185,78 @@ pub async fn create_task(
     .bind(&task_data.priority.unwrap_or(crate::models::TaskPriority::Med))
     .bind(&task_data.due_date)
     .bind(&tags_json)
-    .execute(pool.get_ref())
+    .bind(task_data.parent_id)
+    .bind(task_data.project_id)
+    .bind(&recurrence_rule)
+    .bind(recurrence_series.map(|(series_id, _)| series_id))
+    .bind(recurrence_series.map(|(_, index)| index))
+    .bind(workspace_id)
+    .bind(task_data.assignee_id)
+    .execute(&mut *tx)
     .await;
 
-    match result {
-        Ok(_) => {
-            let task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = $1")
-                .bind(&task_id)
-                .fetch_one(pool.get_ref())
-                .await
-                .unwrap();
+    if let Err(e) = result {
+        return Ok(internal_error_response(&format!("Failed to create task: {}", e)));
+    }
 
-            // 🔔 Create automatic notification for new task
-            let _ = notification_service.notify_task_assigned(
-                user.0.id, 
-                &task.title, 
-                0
-            ).await;
+    let task = match sqlx::query_as::<_, Task>(&format!("{} WHERE id = $1", TASK_SELECT))
+        .bind(task_id)
+        .fetch_one(&mut *tx)
+        .await
+    {
+        Ok(task) => task,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to create task: {}", e))),
+    };
 
-            Ok(created_response(task, "Task created successfully"))
-        }
-        Err(e) => {
-            Ok(internal_error_response(&format!("Failed to create task: {}", e)))
+    let changes = task_events::diff(None, Some(&task));
+    if let Err(e) = task_events::record(&mut *tx, task.id, workspace_id, &task.title, Some(user.0.id), TaskEventType::Created, changes).await {
+        return Ok(internal_error_response(&format!("Failed to create task: {}", e)));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to create task: {}", e)));
+    }
+
+    notify_assignment_change(&notification_service, &task, None, user.0.id).await;
+
+    if let Some(description) = &task.description {
+        if let Err(e) = mentions::sync_mentions(
+            pool.get_ref(), &notification_service, task.id, None, description, &user.0,
+        ).await {
+            tracing::error!("Failed to record mentions for task {}: {}", task.id, e);
         }
     }
+
+    let version = task.version;
+    Ok(etag::with_etag(created_response(task, "Task created successfully"), version))
 }
 
 #[get("/{id}")]
 pub async fn get_task(
+    req: HttpRequest,
     pool: web::Data<PgPool>,
     user: AuthenticatedUser,
     path: web::Path<Uuid>,
 ) -> Result<HttpResponse> {
     let task_id = path.into_inner();
 
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
     let task = sqlx::query_as::<_, Task>(
-        "SELECT * FROM tasks WHERE id = $1 AND user_id = $2"
+        &format!("{} WHERE id = $1", TASK_SELECT)
     )
     .bind(&task_id)
-    .bind(&user.0.id)
     .fetch_optional(pool.get_ref())
     .await;
 
     match task {
-        Ok(Some(task)) => Ok(ok_response(task, "Task retrieved successfully")),
+        Ok(Some(task)) if etag::if_none_match(&req, task.version) => Ok(etag::not_modified_response(task.version)),
+        Ok(Some(task)) => {
+            let version = task.version;
+            Ok(etag::with_etag(ok_response(task, "Task retrieved successfully"), version))
+        }
         Ok(None) => Ok(not_found_response("task")),
         Err(e) => Ok(internal_error_response(&e.to_string()))
     }
@@ -280,6 +264,7 @@ pub async fn get_task(
 
 #[put("/{id}")]
 pub async fn update_task(
+    req: HttpRequest,
     pool: web::Data<PgPool>,
     notification_service: web::Data<NotificationService>,
     user: AuthenticatedUser,
@@ -290,24 +275,65 @@ pub async fn update_task(
         return Ok(validation_error_response(errors));
     }
 
-    let task_id = path.into_inner();
-    let task_data = task_data.into_inner();
-    
+    apply_task_update(&req, &pool, &notification_service, &user, path.into_inner(), task_data.into_inner().into()).await
+}
+
+// Lets patch_task take RFC 7396 bodies; register it on the scope serving patch_task
+pub fn merge_patch_json_config() -> web::JsonConfig {
+    web::JsonConfig::default()
+        .content_type(|mime| mime.essence_str() == "application/merge-patch+json")
+}
+
+// Same as update_task, except that null clears a field
+#[patch("/{id}")]
+pub async fn patch_task(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    task_data: web::Json<PatchTaskRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = task_data.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    apply_task_update(&req, &pool, &notification_service, &user, path.into_inner(), task_data.into_inner()).await
+}
+
+async fn apply_task_update(
+    req: &HttpRequest,
+    pool: &web::Data<PgPool>,
+    notification_service: &NotificationService,
+    user: &AuthenticatedUser,
+    task_id: Uuid,
+    task_data: PatchTaskRequest,
+) -> Result<HttpResponse> {
     // Validate due_date is in the future if provided
-    if let Some(due_date) = &task_data.due_date {
+    if let Some(Some(due_date)) = &task_data.due_date {
         if due_date <= &chrono::Utc::now() {
             let error = ApiError::new("INVALID_DUE_DATE", "Due date must be in the future");
             return Ok(bad_request_response(error));
         }
     }
 
-    // Check if task exists and belongs to user
+    let workspace_id = match access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Write).await {
+        Ok(access) => access.workspace_id,
+        Err(e) => return Ok(e.into_response("task")),
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    // Locked until the update commits, so the history diffs against the row
+    // this update actually replaces
     let existing_task = sqlx::query_as::<_, Task>(
-        "SELECT * FROM tasks WHERE id = $1 AND user_id = $2"
+        "SELECT * FROM tasks WHERE id = $1 FOR UPDATE"
     )
     .bind(&task_id)
-    .bind(&user.0.id)
-    .fetch_optional(pool.get_ref())
+    .fetch_optional(&mut *tx)
     .await;
 
     let existing_task = match existing_task {
@@ -320,13 +346,66 @@ pub async fn update_task(
         }
     };
 
+    if !etag::if_match(req, existing_task.version) {
+        return Ok(etag::precondition_failed_response(existing_task.version));
+    }
+    // With If-Match the write only lands on the version just checked
+    let expected_version = req.headers().contains_key(header::IF_MATCH).then_some(existing_task.version);
+
+    if let Some(Some(parent_id)) = task_data.parent_id {
+        if let Err(response) = validate_parent(pool.get_ref(), workspace_id, parent_id, Some(task_id)).await {
+            return Ok(response);
+        }
+    }
+
+    if let Some(Some(project_id)) = task_data.project_id {
+        if let Err(response) = validate_project(pool.get_ref(), workspace_id, project_id).await {
+            return Ok(response);
+        }
+    }
+
+    // Some(None) stops the task recurring
+    let recurrence_rule = match task_data.recurrence.as_ref().map(|rule| parse_recurrence(rule.as_deref())).transpose() {
+        Ok(rule) => rule,
+        Err(message) => {
+            let error = ApiError::new("INVALID_RECURRENCE", &message);
+            return Ok(bad_request_response(error));
+        }
+    };
+
+    // Work can't start or finish while a blocker is still open
+    let task_progressing = matches!(task_data.status, Some(TaskStatus::Doing) | Some(TaskStatus::Done))
+        && task_data.status.as_ref() != Some(&existing_task.status);
+
+    if task_progressing {
+        let blockers = match task_dependencies::open_blockers(pool.get_ref(), task_id).await {
+            Ok(blockers) => blockers,
+            Err(e) => return Ok(internal_error_response(&e.to_string())),
+        };
+
+        if !blockers.is_empty() {
+            return Ok(blocked_response(blockers));
+        }
+    }
+
     // 🔔 Detect if task is being marked as completed
     let task_being_completed = task_data.status.as_ref() == Some(&crate::models::TaskStatus::Done) 
         && existing_task.status != crate::models::TaskStatus::Done;
 
+    // A task changing status goes to the end of its new column
+    let column_position = match &task_data.status {
+        Some(status) if *status != existing_task.status => {
+            match task_position::position_last(&mut tx, workspace_id, status, task_id).await {
+                Ok(position) => Some(position),
+                Err(e) => return Ok(internal_error_response(&e.to_string())),
+            }
+        }
+        _ => None,
+    };
+
     // Build update query dynamically
     let mut updates = Vec::new();
-    let mut param_count = 3;
+    let mut param_count = 4;
 
     if task_data.title.is_some() {
         updates.push(format!("title = ${}", param_count));
@@ -340,6 +419,10 @@ pub async fn update_task(
         updates.push(format!("status = ${}", param_count));
         param_count += 1;
     }
+    if column_position.is_some() {
+        updates.push(format!("position = ${}", param_count));
+        param_count += 1;
+    }
     if task_data.priority.is_some() {
         updates.push(format!("priority = ${}", param_count));
         param_count += 1;
@@ -350,6 +433,23 @@ pub async fn update_task(
     }
     if task_data.tags.is_some() {
         updates.push(format!("tags = ${}", param_count));
+        param_count += 1;
+    }
+    if task_data.parent_id.is_some() {
+        updates.push(format!("parent_id = ${}", param_count));
+        param_count += 1;
+    }
+    if task_data.project_id.is_some() {
+        updates.push(format!("project_id = ${}", param_count));
+        param_count += 1;
+    }
+    if let Some(rule) = &recurrence_rule {
+        updates.push(format!("recurrence_rule = ${}", param_count));
+        if rule.is_some() {
+            // Tasks that become recurring start a series of their own
+            updates.push("recurrence_series_id = COALESCE(recurrence_series_id, id)".to_string());
+            updates.push("recurrence_index = COALESCE(recurrence_index, 1)".to_string());
+        }
         // Last param_count increment, so no warning about unused assignment
     }
 
@@ -358,11 +458,11 @@ pub async fn update_task(
     }
 
     let query_str = format!(
-        "UPDATE tasks SET {} WHERE id = $1 AND user_id = $2",
+        "UPDATE tasks SET {} WHERE id = $1 AND workspace_id = $2 AND ($3::INTEGER IS NULL OR version = $3)",
         updates.join(", ")
     );
 
-    let mut query = sqlx::query(&query_str).bind(&task_id).bind(&user.0.id);
+    let mut query = sqlx::query(&query_str).bind(task_id).bind(workspace_id).bind(expected_version);
 
     if let Some(title) = &task_data.title {
         query = query.bind(title);
@@ -373,6 +473,9 @@ pub async fn update_task(
     if let Some(status) = &task_data.status {
         query = query.bind(status);
     }
+    if let Some(position) = column_position {
+        query = query.bind(position);
+    }
     if let Some(priority) = &task_data.priority {
         query = query.bind(priority);
     }
@@ -382,60 +485,1166 @@ pub async fn update_task(
     if let Some(tags) = &task_data.tags {
         query = query.bind(json!(tags));
     }
+    if let Some(parent_id) = &task_data.parent_id {
+        query = query.bind(parent_id);
+    }
+    if let Some(project_id) = &task_data.project_id {
+        query = query.bind(project_id);
+    }
+    if let Some(recurrence_rule) = &recurrence_rule {
+        query = query.bind(recurrence_rule);
+    }
 
-    match query.execute(pool.get_ref()).await {
-        Ok(_) => {
-            let updated_task = sqlx::query_as::<_, Task>(
-                "SELECT * FROM tasks WHERE id = $1"
-            )
-            .bind(&task_id)
-            .fetch_one(pool.get_ref())
-            .await
-            .unwrap();
-
-            if task_being_completed {
-                let _ = notification_service.notify_task_completed(
-                    user.0.id, 
-                    &updated_task.title, 
-                    0
-                ).await;
-            }
-
-            Ok(ok_response(updated_task, "Task updated successfully"))
+    match query.execute(&mut *tx).await {
+        Ok(result) if result.rows_affected() == 0 => {
+            // Changed by someone else between the check and the write
+            let current = sqlx::query_scalar::<_, i32>("SELECT version FROM tasks WHERE id = $1")
+                .bind(task_id)
+                .fetch_one(&mut *tx)
+                .await;
+            return Ok(match current {
+                Ok(version) => etag::precondition_failed_response(version),
+                Err(e) => internal_error_response(&format!("Failed to update task: {}", e)),
+            });
         }
-        Err(e) => {
-            Ok(internal_error_response(&format!("Failed to update task: {}", e)))
+        Ok(_) => {}
+        Err(e) => return Ok(internal_error_response(&format!("Failed to update task: {}", e))),
+    }
+
+    // The history entry diffs the row as locked before the update
+    let changed_task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = $1")
+        .bind(task_id)
+        .fetch_one(&mut *tx)
+        .await;
+    let recorded = match changed_task {
+        Ok(changed_task) => task_events::record_update(&mut *tx, &existing_task, &changed_task, user.0.id).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = recorded {
+        return Ok(internal_error_response(&format!("Failed to update task: {}", e)));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to update task: {}", e)));
+    }
+
+    let updated_task = sqlx::query_as::<_, Task>(
+        &format!("{} WHERE id = $1", TASK_SELECT)
+    )
+    .bind(task_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap();
+
+    // A cleared description drops its mentions
+    if let Some(description) = &task_data.description {
+        if let Err(e) = mentions::sync_mentions(
+            pool.get_ref(), notification_service, task_id, None, description.as_deref().unwrap_or(""), &user.0,
+        ).await {
+            tracing::error!("Failed to record mentions for task {}: {}", task_id, e);
         }
     }
+
+    if task_being_completed {
+        task_completed(pool.get_ref(), notification_service, user.0.id, &updated_task).await;
+    }
+
+    let version = updated_task.version;
+    Ok(etag::with_etag(ok_response(updated_task, "Task updated successfully"), version))
 }
 
 #[delete("/{id}")]
 pub async fn delete_task(
+    req: HttpRequest,
     pool: web::Data<PgPool>,
     user: AuthenticatedUser,
     path: web::Path<Uuid>,
+    query: web::Query<DeleteTaskQuery>,
 ) -> Result<HttpResponse> {
     let task_id = path.into_inner();
 
-    let result = sqlx::query(
-        "DELETE FROM tasks WHERE id = $1 AND user_id = $2"
+    let workspace_id = match access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Write).await {
+        Ok(access) => access.workspace_id,
+        Err(e) => return Ok(e.into_response("task")),
+    };
+
+    let children = query.children.unwrap_or_default();
+    let task_ids = match children {
+        ChildrenOnDelete::Orphan => vec![task_id],
+        ChildrenOnDelete::Delete => match task_tree::subtree_ids(pool.get_ref(), task_id).await {
+            Ok(ids) => ids,
+            Err(e) => return Ok(internal_error_response(&format!("Failed to delete task: {}", e))),
+        },
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let version = sqlx::query_scalar::<_, i32>(
+        "SELECT version FROM tasks WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"
     )
-    .bind(&task_id)
-    .bind(&user.0.id)
-    .execute(pool.get_ref())
+    .bind(task_id)
+    .fetch_optional(&mut *tx)
     .await;
 
-    match result {
-        Ok(rows) => {
-            if rows.rows_affected() == 0 {
-                Ok(not_found_response("task"))
-            } else {
-                let success_data = serde_json::json!({"deleted": true});
-                Ok(ok_response(success_data, "Task deleted successfully"))
+    match version {
+        Ok(Some(version)) if !etag::if_match(&req, version) => return Ok(etag::precondition_failed_response(version)),
+        Ok(Some(_)) => {}
+        Ok(None) => return Ok(not_found_response("task")),
+        Err(e) => return Ok(internal_error_response(&format!("Failed to delete task: {}", e))),
+    }
+
+    // Tasks trashed together share deleted_at, which is how they are restored together
+    let trashed_tasks = sqlx::query_as::<_, Task>(
+        "UPDATE tasks SET deleted_at = NOW() WHERE id = ANY($1) AND workspace_id = $2 AND deleted_at IS NULL RETURNING *"
+    )
+    .bind(&task_ids)
+    .bind(workspace_id)
+    .fetch_all(&mut *tx)
+    .await;
+
+    let trashed_tasks = match trashed_tasks {
+        Ok(tasks) if tasks.is_empty() => return Ok(not_found_response("task")),
+        Ok(tasks) => tasks,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to delete task: {}", e))),
+    };
+
+    // The history keeps each task's last state
+    for task in &trashed_tasks {
+        let changes = task_events::diff(Some(task), None);
+        if let Err(e) = task_events::record(&mut *tx, task.id, workspace_id, &task.title, Some(user.0.id), TaskEventType::Deleted, changes).await {
+            return Ok(internal_error_response(&format!("Failed to delete task: {}", e)));
+        }
+    }
+
+    if children == ChildrenOnDelete::Orphan {
+        if let Err(e) = orphan_children(&mut tx, task_id, workspace_id, user.0.id).await {
+            return Ok(internal_error_response(&format!("Failed to delete task: {}", e)));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to delete task: {}", e)));
+    }
+
+    let success_data = serde_json::json!({
+        "deleted": true,
+        "deleted_count": trashed_tasks.len()
+    });
+    Ok(ok_response(success_data, "Task moved to trash"))
+}
+
+// Children left behind by a trashed task become root tasks
+async fn orphan_children(conn: &mut PgConnection, task_id: Uuid, workspace_id: Uuid, actor_id: Uuid) -> Result<(), sqlx::Error> {
+    let orphans = sqlx::query_as::<_, (Uuid, String)>(
+        "UPDATE tasks SET parent_id = NULL WHERE parent_id = $1 AND deleted_at IS NULL RETURNING id, title"
+    )
+    .bind(task_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for (orphan_id, orphan_title) in orphans {
+        let mut changes = serde_json::Map::new();
+        changes.insert("parent_id".to_string(), json!({ "old": task_id, "new": null }));
+        task_events::record(
+            &mut *conn, orphan_id, workspace_id, &orphan_title, Some(actor_id), TaskEventType::Updated, changes,
+        ).await?;
+    }
+
+    Ok(())
+}
+
+#[post("/bulk")]
+pub async fn bulk_update_tasks(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: AuthenticatedUser,
+    body: web::Json<BulkTaskRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = body.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let BulkTaskRequest { mut ids, operation, mode } = body.into_inner();
+    if let BulkOperation::AddTags { tags } | BulkOperation::RemoveTags { tags } = &operation {
+        if tags.is_empty() {
+            return Ok(bad_request_response(ApiError::new("INVALID_TAGS", "At least one tag is required")));
+        }
+    }
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let mut results = Vec::with_capacity(ids.len());
+    let mut completed = Vec::new();
+    for task_id in ids {
+        // A savepoint per task, so a failed one leaves the others alone
+        let mut item_tx = match tx.begin().await {
+            Ok(item_tx) => item_tx,
+            Err(e) => return Ok(internal_error_response(&format!("Failed to update tasks: {}", e))),
+        };
+
+        let (status, error) = match apply_bulk_operation(&mut item_tx, pool.get_ref(), user.0.id, task_id, &operation).await {
+            Ok(changed) => {
+                if let Err(e) = item_tx.commit().await {
+                    return Ok(internal_error_response(&format!("Failed to update tasks: {}", e)));
+                }
+                match changed {
+                    Some(task) => {
+                        if operation == (BulkOperation::SetStatus { status: TaskStatus::Done }) {
+                            completed.push(task);
+                        }
+                        (BulkItemStatus::Updated, None)
+                    }
+                    None => (BulkItemStatus::Unchanged, None),
+                }
+            }
+            Err(error) => {
+                if let Err(e) = item_tx.rollback().await {
+                    return Ok(internal_error_response(&format!("Failed to update tasks: {}", e)));
+                }
+                (BulkItemStatus::Failed, Some(error))
             }
+        };
+        results.push(BulkItemResult { id: task_id, status, error });
+    }
+
+    let failed = results.iter().filter(|result| result.status == BulkItemStatus::Failed).count();
+    if failed > 0 && mode == BulkMode::AllOrNothing {
+        // Dropping the transaction rolls every task back
+        let failures: Vec<_> = results.into_iter().filter(|result| result.status == BulkItemStatus::Failed).collect();
+        return Ok(bad_request_response(ApiError::with_fields(
+            "BULK_OPERATION_FAILED",
+            "No tasks were changed because some of them could not be",
+            json!({ "results": failures }),
+        )));
+    }
+
+    // Parents whose last open subtasks were completed here finish with them
+    let mut completed_parents = Vec::new();
+    for task in &completed {
+        match complete_parents(&mut tx, user.0.id, task.workspace_id, task.id).await {
+            Ok(parents) => completed_parents.extend(parents),
+            Err(e) => return Ok(internal_error_response(&format!("Failed to update tasks: {}", e))),
         }
-        Err(e) => {
-            Ok(internal_error_response(&format!("Failed to delete task: {}", e)))
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to update tasks: {}", e)));
+    }
+
+    // One notification per recipient for everything completed or unblocked here;
+    // the actor hears about their own tasks, owners about the parents
+    let mut completed_by_recipient: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    completed_by_recipient.entry(user.0.id).or_default().extend(completed.iter().map(|task| task.id));
+    for parent in &completed_parents {
+        completed_by_recipient.entry(parent.user_id).or_default().push(parent.id);
+    }
+
+    let mut unblocked_by_owner: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    let blocker_ids = completed.iter().map(|task| task.id).chain(completed_parents.iter().map(|parent| parent.id));
+    for blocker_id in blocker_ids {
+        match task_dependencies::unblocked_by(pool.get_ref(), blocker_id).await {
+            Ok(unblocked) => {
+                for task in unblocked {
+                    let task_ids = unblocked_by_owner.entry(task.user_id).or_default();
+                    if !task_ids.contains(&task.id) {
+                        task_ids.push(task.id);
+                    }
+                }
+            }
+            Err(e) => tracing::error!("Failed to look up unblocked tasks: {}", e),
+        }
+    }
+
+    for (recipient_id, task_ids) in completed_by_recipient {
+        let _ = notification_service.notify_tasks_completed(recipient_id, &task_ids).await;
+    }
+    for (owner_id, task_ids) in unblocked_by_owner {
+        let _ = notification_service.notify_tasks_unblocked(owner_id, &task_ids).await;
+    }
+
+    // Completing a recurring task schedules its next instance
+    for task in &completed {
+        if let Err(e) = task_recurrence::generate_next_instance(pool.get_ref(), task, Some(user.0.id)).await {
+            tracing::error!("Failed to generate next recurring instance: {}", e);
         }
     }
-}
\ No newline at end of file
+
+    let updated = results.iter().filter(|result| result.status == BulkItemStatus::Updated).count();
+    let response = BulkTaskResponse { results, updated, failed };
+    Ok(ok_response(response, "Bulk operation applied"))
+}
+
+// Applies a bulk operation to one task within the bulk transaction. Ok(None)
+// means the task already was as asked.
+async fn apply_bulk_operation(
+    conn: &mut PgConnection,
+    pool: &PgPool,
+    user_id: Uuid,
+    task_id: Uuid,
+    operation: &BulkOperation,
+) -> std::result::Result<Option<Task>, BulkItemError> {
+    let failure = |code: &str, message: &str| BulkItemError { code: code.to_string(), message: message.to_string() };
+    let database_failure = |e: sqlx::Error| failure("DATABASE_ERROR", &e.to_string());
+
+    let workspace_id = match access::authorize_task(pool, task_id, user_id, Permission::Write).await {
+        Ok(access) => access.workspace_id,
+        Err(AccessError::NotFound) => return Err(failure("NOT_FOUND", "Task not found")),
+        Err(AccessError::Forbidden) => return Err(failure("FORBIDDEN", "Your workspace role does not allow this action")),
+        Err(AccessError::Database(e)) => return Err(database_failure(e)),
+    };
+
+    // Also catches tasks trashed earlier in the same request
+    let before = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
+        .bind(task_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(database_failure)?
+        .ok_or_else(|| failure("NOT_FOUND", "Task not found"))?;
+
+    let after = match operation {
+        BulkOperation::SetStatus { status } => {
+            if before.status == *status {
+                return Ok(None);
+            }
+            if matches!(status, TaskStatus::Doing | TaskStatus::Done) {
+                let blockers = task_dependencies::open_blockers(&mut *conn, task_id).await.map_err(database_failure)?;
+                if !blockers.is_empty() {
+                    return Err(failure("TASK_BLOCKED", "Task is blocked by unfinished tasks"));
+                }
+            }
+            let position = task_position::position_last(&mut *conn, workspace_id, status, task_id)
+                .await
+                .map_err(database_failure)?;
+            sqlx::query_as::<_, Task>("UPDATE tasks SET status = $2, position = $3 WHERE id = $1 RETURNING *")
+                .bind(task_id)
+                .bind(status)
+                .bind(position)
+                .fetch_one(&mut *conn)
+                .await
+        }
+        BulkOperation::SetPriority { priority } => {
+            if before.priority == *priority {
+                return Ok(None);
+            }
+            sqlx::query_as::<_, Task>("UPDATE tasks SET priority = $2 WHERE id = $1 RETURNING *")
+                .bind(task_id)
+                .bind(priority)
+                .fetch_one(&mut *conn)
+                .await
+        }
+        BulkOperation::AddTags { tags } | BulkOperation::RemoveTags { tags } => {
+            let current: Vec<String> = serde_json::from_value(before.tags.clone()).unwrap_or_default();
+            let mut updated = current.clone();
+            if matches!(operation, BulkOperation::AddTags { .. }) {
+                for tag in tags {
+                    if !updated.contains(tag) {
+                        updated.push(tag.clone());
+                    }
+                }
+            } else {
+                updated.retain(|tag| !tags.contains(tag));
+            }
+            if updated == current {
+                return Ok(None);
+            }
+            sqlx::query_as::<_, Task>("UPDATE tasks SET tags = $2 WHERE id = $1 RETURNING *")
+                .bind(task_id)
+                .bind(json!(updated))
+                .fetch_one(&mut *conn)
+                .await
+        }
+        BulkOperation::MoveToProject { project_id } => {
+            if before.project_id == *project_id {
+                return Ok(None);
+            }
+            if let Some(project_id) = project_id {
+                match project_in_workspace(pool, *project_id, workspace_id).await {
+                    Ok(true) => {}
+                    Ok(false) => return Err(failure("INVALID_PROJECT", "Project not found")),
+                    Err(e) => return Err(database_failure(e)),
+                }
+            }
+            sqlx::query_as::<_, Task>("UPDATE tasks SET project_id = $2 WHERE id = $1 RETURNING *")
+                .bind(task_id)
+                .bind(project_id)
+                .fetch_one(&mut *conn)
+                .await
+        }
+        BulkOperation::Delete => {
+            let trashed = sqlx::query_as::<_, Task>("UPDATE tasks SET deleted_at = NOW() WHERE id = $1 RETURNING *")
+                .bind(task_id)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(database_failure)?;
+            let changes = task_events::diff(Some(&trashed), None);
+            task_events::record(&mut *conn, task_id, workspace_id, &trashed.title, Some(user_id), TaskEventType::Deleted, changes)
+                .await
+                .map_err(database_failure)?;
+            orphan_children(conn, task_id, workspace_id, user_id).await.map_err(database_failure)?;
+            return Ok(Some(trashed));
+        }
+        BulkOperation::Archive => {
+            if before.archived_at.is_some() {
+                return Ok(None);
+            }
+            let archived = sqlx::query_as::<_, Task>("UPDATE tasks SET archived_at = NOW() WHERE id = $1 RETURNING *")
+                .bind(task_id)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(database_failure)?;
+            task_events::record(&mut *conn, task_id, workspace_id, &archived.title, Some(user_id), TaskEventType::Archived, serde_json::Map::new())
+                .await
+                .map_err(database_failure)?;
+            return Ok(Some(archived));
+        }
+    };
+
+    let after = after.map_err(database_failure)?;
+    task_events::record_update(&mut *conn, &before, &after, user_id).await.map_err(database_failure)?;
+    Ok(Some(after))
+}
+
+#[get("/trash")]
+pub async fn get_trash(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<TrashQuery>,
+) -> Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let position = match cursor::position::<Uuid>(query.cursor.as_deref()) {
+        Ok(position) => position,
+        Err(message) => return Ok(bad_request_response(ApiError::new("INVALID_CURSOR", &message))),
+    };
+
+    let mut builder = QueryBuilder::<Postgres>::new(TASK_SELECT);
+    builder
+        .push(format!(" WHERE workspace_id IN ({}", MEMBER_WORKSPACES))
+        .push_bind(user.0.id)
+        .push(") AND deleted_at IS NOT NULL");
+    if let Some(workspace_id) = query.workspace_id {
+        builder.push(" AND workspace_id = ").push_bind(workspace_id);
+    }
+    if let Some(position) = &position {
+        builder
+            .push(" AND (deleted_at, id) < (")
+            .push_bind(position.at)
+            .push(", ")
+            .push_bind(position.id)
+            .push(")");
+    }
+    builder
+        .push(" ORDER BY deleted_at DESC, id DESC LIMIT ")
+        .push_bind(limit + 1);
+
+    let mut tasks = match builder.build_query_as::<Task>().fetch_all(pool.get_ref()).await {
+        Ok(tasks) => tasks,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to fetch trash: {}", e))),
+    };
+
+    let next_cursor = cursor::next_page(&mut tasks, limit, |last| last.deleted_at.map(|deleted_at| (deleted_at, last.id)));
+
+    Ok(ok_response(TrashPage { tasks, next_cursor }, "Trash retrieved successfully"))
+}
+
+#[post("/{id}/restore")]
+pub async fn restore_task(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+
+    let workspace_id = match access::authorize_trashed_task(pool.get_ref(), task_id, user.0.id, Permission::Write).await {
+        Ok(access) => access.workspace_id,
+        Err(e) => return Ok(e.into_response("task")),
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let trashed_task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = $1 AND deleted_at IS NOT NULL FOR UPDATE")
+        .bind(task_id)
+        .fetch_optional(&mut *tx)
+        .await;
+
+    let trashed_task = match trashed_task {
+        Ok(Some(task)) => task,
+        Ok(None) => return Ok(not_found_response("task")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    // Subtasks deleted along with the task come back with it. A task whose
+    // parent is still in the trash is restored as a root task.
+    let restored_tasks = sqlx::query_as::<_, Task>(
+        r#"
+        WITH RECURSIVE subtree AS (
+            SELECT id, deleted_at FROM tasks WHERE id = $1
+            UNION
+            SELECT t.id, t.deleted_at FROM tasks t JOIN subtree s ON t.parent_id = s.id WHERE t.deleted_at = s.deleted_at
+        )
+        UPDATE tasks SET
+            deleted_at = NULL,
+            parent_id = CASE
+                WHEN tasks.id = $1 AND EXISTS (
+                    SELECT 1 FROM tasks p WHERE p.id = tasks.parent_id AND p.deleted_at IS NOT NULL
+                ) THEN NULL
+                ELSE tasks.parent_id
+            END
+        WHERE tasks.id IN (SELECT id FROM subtree)
+        RETURNING *
+        "#
+    )
+    .bind(task_id)
+    .fetch_all(&mut *tx)
+    .await;
+
+    let restored_tasks = match restored_tasks {
+        Ok(tasks) => tasks,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to restore task: {}", e))),
+    };
+
+    for task in &restored_tasks {
+        let changes = if task.id == task_id {
+            task_events::diff(Some(&trashed_task), Some(task))
+        } else {
+            serde_json::Map::new()
+        };
+        if let Err(e) = task_events::record(&mut *tx, task.id, workspace_id, &task.title, Some(user.0.id), TaskEventType::Restored, changes).await {
+            return Ok(internal_error_response(&format!("Failed to restore task: {}", e)));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to restore task: {}", e)));
+    }
+
+    let task = sqlx::query_as::<_, Task>(&format!("{} WHERE id = $1", TASK_SELECT))
+        .bind(task_id)
+        .fetch_one(pool.get_ref())
+        .await;
+
+    match task {
+        Ok(task) => Ok(ok_response(task, "Task restored successfully")),
+        Err(e) => Ok(internal_error_response(&e.to_string())),
+    }
+}
+
+#[delete("/trash/{id}")]
+pub async fn purge_task(
+    pool: web::Data<PgPool>,
+    storage: web::Data<dyn StorageBackend>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+
+    if let Err(e) = access::authorize_trashed_task(pool.get_ref(), task_id, user.0.id, Permission::Write).await {
+        return Ok(e.into_response("task"));
+    }
+
+    let task_ids = match trash::trashed_subtree_ids(pool.get_ref(), task_id).await {
+        Ok(ids) => ids,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to delete task: {}", e))),
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let purged = match trash::purge(&mut tx, &task_ids, Some(user.0.id)).await {
+        Ok(purged) if purged.is_empty() => return Ok(not_found_response("task")),
+        Ok(purged) => purged,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to delete task: {}", e))),
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to delete task: {}", e)));
+    }
+
+    // Attachment rows went with the tasks; their blobs go now
+    if let Err(e) = attachments::purge_deleted_blobs(pool.get_ref(), storage.get_ref()).await {
+        tracing::error!("Failed to purge attachment blobs: {}", e);
+    }
+
+    let success_data = serde_json::json!({
+        "deleted": true,
+        "deleted_count": purged.len()
+    });
+    Ok(ok_response(success_data, "Task deleted permanently"))
+}
+
+#[post("/{id}/move")]
+pub async fn move_task(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<MoveTaskRequest>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+    let MoveTaskRequest { status, before_id, after_id } = body.into_inner();
+
+    let workspace_id = match access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Write).await {
+        Ok(access) => access.workspace_id,
+        Err(e) => return Ok(e.into_response("task")),
+    };
+
+    let neighbours: Vec<Uuid> = before_id.into_iter().chain(after_id).collect();
+    if neighbours.contains(&task_id) || before_id.is_some() && before_id == after_id {
+        return Ok(bad_request_response(ApiError::new("INVALID_NEIGHBOUR", "A task can't be moved next to itself")));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let existing_task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
+        .bind(task_id)
+        .fetch_optional(&mut *tx)
+        .await;
+    let existing_task = match existing_task {
+        Ok(Some(task)) => task,
+        Ok(None) => return Ok(not_found_response("task")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let in_column = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM tasks WHERE id = ANY($1) AND workspace_id = $2 AND status = $3 AND deleted_at IS NULL"
+    )
+    .bind(&neighbours)
+    .bind(workspace_id)
+    .bind(&status)
+    .fetch_one(&mut *tx)
+    .await;
+    match in_column {
+        Ok(count) if count as usize == neighbours.len() => {}
+        Ok(_) => return Ok(bad_request_response(ApiError::new("INVALID_NEIGHBOUR", "Neighbouring tasks must be in the target column"))),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    }
+
+    // Work can't start or finish while a blocker is still open
+    if matches!(status, TaskStatus::Doing | TaskStatus::Done) && status != existing_task.status {
+        match task_dependencies::open_blockers(&mut *tx, task_id).await {
+            Ok(blockers) if !blockers.is_empty() => return Ok(blocked_response(blockers)),
+            Ok(_) => {}
+            Err(e) => return Ok(internal_error_response(&e.to_string())),
+        }
+    }
+
+    let position = match task_position::position_between(&mut tx, workspace_id, &status, task_id, before_id, after_id).await {
+        Ok(Some(position)) => position,
+        Ok(None) => return Ok(bad_request_response(ApiError::new("INVALID_NEIGHBOUR", "before_id must come before after_id"))),
+        Err(e) => return Ok(internal_error_response(&format!("Failed to move task: {}", e))),
+    };
+
+    let moved_task = sqlx::query_as::<_, Task>("UPDATE tasks SET status = $2, position = $3 WHERE id = $1 RETURNING *")
+        .bind(task_id)
+        .bind(&status)
+        .bind(position)
+        .fetch_one(&mut *tx)
+        .await;
+    let recorded = match moved_task {
+        Ok(moved_task) => task_events::record_update(&mut *tx, &existing_task, &moved_task, user.0.id).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = recorded {
+        return Ok(internal_error_response(&format!("Failed to move task: {}", e)));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to move task: {}", e)));
+    }
+
+    let task = match sqlx::query_as::<_, Task>(&format!("{} WHERE id = $1", TASK_SELECT))
+        .bind(task_id)
+        .fetch_one(pool.get_ref())
+        .await
+    {
+        Ok(task) => task,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    if status == TaskStatus::Done && existing_task.status != TaskStatus::Done {
+        task_completed(pool.get_ref(), &notification_service, user.0.id, &task).await;
+    }
+
+    let version = task.version;
+    Ok(etag::with_etag(ok_response(task, "Task moved successfully"), version))
+}
+
+#[post("/{id}/archive")]
+pub async fn archive_task(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    Ok(change_archived(pool.get_ref(), user.0.id, path.into_inner(), true).await)
+}
+
+#[post("/{id}/unarchive")]
+pub async fn unarchive_task(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    Ok(change_archived(pool.get_ref(), user.0.id, path.into_inner(), false).await)
+}
+
+#[put("/{id}/assignee")]
+pub async fn assign_task(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<AssignTaskRequest>,
+) -> Result<HttpResponse> {
+    Ok(change_assignee(pool.get_ref(), &notification_service, user.0.id, path.into_inner(), body.assignee_id).await)
+}
+
+#[delete("/{id}/assignee")]
+pub async fn unassign_task(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    Ok(change_assignee(pool.get_ref(), &notification_service, user.0.id, path.into_inner(), None).await)
+}
+
+#[get("/{id}/children")]
+pub async fn get_task_children(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
+    let children = sqlx::query_as::<_, Task>(
+        &format!("{} WHERE parent_id = $1 AND deleted_at IS NULL ORDER BY created_at ASC", TASK_SELECT)
+    )
+    .bind(task_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match children {
+        Ok(children) => Ok(ok_response(children, "Subtasks retrieved successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to fetch subtasks: {}", e))),
+    }
+}
+
+#[get("/{id}/tree")]
+pub async fn get_task_tree(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let task_id = path.into_inner();
+
+    if let Err(e) = access::authorize_task(pool.get_ref(), task_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("task"));
+    }
+
+    let root = sqlx::query_as::<_, Task>(
+        &format!("{} WHERE id = $1", TASK_SELECT)
+    )
+    .bind(task_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let root = match root {
+        Ok(Some(task)) => task,
+        Ok(None) => return Ok(not_found_response("task")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let descendants = sqlx::query_as::<_, Task>(
+        &format!(
+            r#"
+            WITH RECURSIVE subtree AS (
+                SELECT id FROM tasks WHERE parent_id = $1 AND deleted_at IS NULL
+                UNION
+                SELECT t.id FROM tasks t JOIN subtree s ON t.parent_id = s.id WHERE t.deleted_at IS NULL
+            )
+            {} WHERE id IN (SELECT id FROM subtree) ORDER BY created_at ASC
+            "#,
+            TASK_SELECT
+        )
+    )
+    .bind(task_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let descendants = match descendants {
+        Ok(tasks) => tasks,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to fetch task tree: {}", e))),
+    };
+
+    let mut children_by_parent: HashMap<Uuid, Vec<Task>> = HashMap::new();
+    for task in descendants {
+        if let Some(parent_id) = task.parent_id {
+            children_by_parent.entry(parent_id).or_default().push(task);
+        }
+    }
+
+    Ok(ok_response(build_tree(root, &mut children_by_parent), "Task tree retrieved successfully"))
+}
+
+// Archives or unarchives a task; tasks already in that state are returned unchanged
+async fn change_archived(pool: &PgPool, user_id: Uuid, task_id: Uuid, archive: bool) -> HttpResponse {
+    let workspace_id = match access::authorize_task(pool, task_id, user_id, Permission::Write).await {
+        Ok(access) => access.workspace_id,
+        Err(e) => return e.into_response("task"),
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_error_response(&e.to_string()),
+    };
+
+    let changed = sqlx::query_scalar::<_, String>(
+        r#"
+        UPDATE tasks SET archived_at = CASE WHEN $2 THEN NOW() END
+        WHERE id = $1 AND (archived_at IS NULL) = $2
+        RETURNING title
+        "#
+    )
+    .bind(task_id)
+    .bind(archive)
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let changed = match changed {
+        Ok(changed) => changed,
+        Err(e) => return internal_error_response(&format!("Failed to update task: {}", e)),
+    };
+
+    if let Some(title) = changed {
+        let event_type = if archive { TaskEventType::Archived } else { TaskEventType::Unarchived };
+        if let Err(e) = task_events::record(&mut *tx, task_id, workspace_id, &title, Some(user_id), event_type, serde_json::Map::new()).await {
+            return internal_error_response(&format!("Failed to update task: {}", e));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return internal_error_response(&format!("Failed to update task: {}", e));
+    }
+
+    let task = sqlx::query_as::<_, Task>(&format!("{} WHERE id = $1", TASK_SELECT))
+        .bind(task_id)
+        .fetch_one(pool)
+        .await;
+
+    match task {
+        Ok(task) if archive => ok_response(task, "Task archived successfully"),
+        Ok(task) => ok_response(task, "Task unarchived successfully"),
+        Err(e) => internal_error_response(&e.to_string()),
+    }
+}
+
+async fn change_assignee(
+    pool: &PgPool,
+    notification_service: &NotificationService,
+    user_id: Uuid,
+    task_id: Uuid,
+    assignee_id: Option<Uuid>,
+) -> HttpResponse {
+    let workspace_id = match access::authorize_task(pool, task_id, user_id, Permission::Write).await {
+        Ok(access) => access.workspace_id,
+        Err(e) => return e.into_response("task"),
+    };
+
+    if let Some(assignee_id) = assignee_id {
+        if let Err(response) = validate_assignee(pool, workspace_id, assignee_id).await {
+            return response;
+        }
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_error_response(&e.to_string()),
+    };
+
+    let previous = sqlx::query_scalar::<_, Option<Uuid>>(
+        r#"
+        WITH previous AS (SELECT assignee_id FROM tasks WHERE id = $1 FOR UPDATE)
+        UPDATE tasks SET assignee_id = $2
+        FROM previous
+        WHERE tasks.id = $1
+        RETURNING previous.assignee_id
+        "#
+    )
+    .bind(task_id)
+    .bind(assignee_id)
+    .fetch_one(&mut *tx)
+    .await;
+
+    let previous = match previous {
+        Ok(previous) => previous,
+        Err(e) => return internal_error_response(&format!("Failed to assign task: {}", e)),
+    };
+
+    let task = match sqlx::query_as::<_, Task>(&format!("{} WHERE id = $1", TASK_SELECT))
+        .bind(task_id)
+        .fetch_one(&mut *tx)
+        .await
+    {
+        Ok(task) => task,
+        Err(e) => return internal_error_response(&e.to_string()),
+    };
+
+    if previous != task.assignee_id {
+        let mut changes = serde_json::Map::new();
+        changes.insert("assignee_id".to_string(), json!({ "old": previous, "new": task.assignee_id }));
+        if let Err(e) = task_events::record(
+            &mut *tx, task.id, workspace_id, &task.title, Some(user_id), TaskEventType::Updated, changes,
+        ).await {
+            return internal_error_response(&format!("Failed to assign task: {}", e));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return internal_error_response(&format!("Failed to assign task: {}", e));
+    }
+
+    notify_assignment_change(notification_service, &task, previous, user_id).await;
+    let message = match assignee_id {
+        Some(_) => "Task assigned successfully",
+        None => "Task unassigned successfully",
+    };
+    ok_response(task, message)
+}
+
+// Tells the new and previous assignee about a change, unless they made it themselves
+async fn notify_assignment_change(
+    notification_service: &NotificationService,
+    task: &Task,
+    previous: Option<Uuid>,
+    actor_id: Uuid,
+) {
+    if previous == task.assignee_id {
+        return;
+    }
+
+    if let Some(previous) = previous.filter(|&id| id != actor_id) {
+        let _ = notification_service.notify_task_unassigned(previous, &task.title, task.id).await;
+    }
+    if let Some(assignee) = task.assignee_id.filter(|&id| id != actor_id) {
+        let _ = notification_service.notify_task_assigned(assignee, &task.title, task.id).await;
+    }
+}
+
+// 409 for work starting or finishing while blockers are open
+fn blocked_response(blockers: Vec<(Uuid, String)>) -> HttpResponse {
+    let blocked_by: Vec<_> = blockers
+        .into_iter()
+        .map(|(id, title)| json!({ "id": id, "title": title }))
+        .collect();
+    HttpResponse::Conflict().json(ApiError::with_fields(
+        "TASK_BLOCKED",
+        "Task is blocked by unfinished tasks",
+        json!({ "blocked_by": blocked_by }),
+    ))
+}
+
+// Follow-up to a single task being marked done by `actor_id`
+async fn task_completed(pool: &PgPool, notification_service: &NotificationService, actor_id: Uuid, task: &Task) {
+    let completed_parents = completion_follow_ups(
+        pool, notification_service, actor_id, task.workspace_id, task.id, &task.title,
+    ).await;
+
+    for parent in completed_parents {
+        let _ = notification_service.notify_task_completed(
+            parent.user_id,
+            &parent.title,
+            parent.id
+        ).await;
+    }
+
+    let _ = notification_service.notify_task_completed(
+        actor_id,
+        &task.title,
+        task.id
+    ).await;
+
+    // Completing a recurring task schedules its next instance
+    if let Err(e) = task_recurrence::generate_next_instance(pool, task, Some(actor_id)).await {
+        tracing::error!("Failed to generate next recurring instance: {}", e);
+    }
+}
+
+// Follow-up to `task_id` being marked done: parents whose last open subtask
+// it was are completed too, and owners of tasks waiting only on any of them
+// are told. Returns the parents completed along the way.
+async fn completion_follow_ups(
+    pool: &PgPool,
+    notification_service: &NotificationService,
+    actor_id: Uuid,
+    workspace_id: Uuid,
+    task_id: Uuid,
+    title: &str,
+) -> Vec<CompletedParent> {
+    let completed_parents = async {
+        let mut tx = pool.begin().await?;
+        let parents = complete_parents(&mut tx, actor_id, workspace_id, task_id).await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(parents)
+    }
+    .await
+    .unwrap_or_else(|e| {
+        tracing::error!("Failed to complete parents of task {}: {}", task_id, e);
+        Vec::new()
+    });
+
+    notify_unblocked(pool, notification_service, task_id, title).await;
+    for parent in &completed_parents {
+        notify_unblocked(pool, notification_service, parent.id, &parent.title).await;
+    }
+
+    completed_parents
+}
+
+// Completes the parents whose last open subtask `task_id` was, recording each
+// in the task history
+async fn complete_parents(
+    conn: &mut PgConnection,
+    actor_id: Uuid,
+    workspace_id: Uuid,
+    task_id: Uuid,
+) -> Result<Vec<CompletedParent>, sqlx::Error> {
+    let completed_parents = task_tree::complete_finished_ancestors(&mut *conn, task_id).await?;
+
+    for parent in &completed_parents {
+        let mut changes = serde_json::Map::new();
+        changes.insert("status".to_string(), json!({ "old": parent.previous_status, "new": TaskStatus::Done }));
+        task_events::record(
+            &mut *conn, parent.id, workspace_id, &parent.title, Some(actor_id), TaskEventType::StatusChanged, changes,
+        ).await?;
+    }
+
+    Ok(completed_parents)
+}
+
+// Tells owners of tasks waiting only on `blocker_id` that they can start
+async fn notify_unblocked(
+    pool: &PgPool,
+    notification_service: &NotificationService,
+    blocker_id: Uuid,
+    blocker_title: &str,
+) {
+    let unblocked = match task_dependencies::unblocked_by(pool, blocker_id).await {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            tracing::error!("Failed to look up unblocked tasks: {}", e);
+            return;
+        }
+    };
+
+    for task in unblocked {
+        let _ = notification_service
+            .notify_task_unblocked(task.user_id, &task.title, task.id, blocker_title)
+            .await;
+    }
+}
+
+fn build_tree(task: Task, children_by_parent: &mut HashMap<Uuid, Vec<Task>>) -> TaskTreeNode {
+    let children = children_by_parent
+        .remove(&task.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| build_tree(child, children_by_parent))
+        .collect();
+
+    TaskTreeNode { task, children }
+}
+
+// Validates an RRULE and returns it in normalized form
+fn parse_recurrence(recurrence: Option<&str>) -> std::result::Result<Option<String>, String> {
+    let rule = recurrence.map(RecurrenceRule::parse).transpose()?;
+    Ok(rule.map(|rule| rule.to_string()))
+}
+
+pub(crate) async fn task_in_workspace(pool: &PgPool, task_id: Uuid, workspace_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL)"
+    )
+    .bind(task_id)
+    .bind(workspace_id)
+    .fetch_one(pool)
+    .await
+}
+
+// Checks that `parent_id` can become the parent of `task_id` (None when creating)
+async fn validate_parent(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    parent_id: Uuid,
+    task_id: Option<Uuid>,
+) -> std::result::Result<(), HttpResponse> {
+    let db_error = |e: sqlx::Error| internal_error_response(&e.to_string());
+
+    if !task_in_workspace(pool, parent_id, workspace_id).await.map_err(db_error)? {
+        return Err(bad_request_response(ApiError::new("INVALID_PARENT", "Parent task not found")));
+    }
+
+    let subtree_height = match task_id {
+        Some(task_id) => {
+            let subtree = task_tree::subtree_ids(pool, task_id).await.map_err(db_error)?;
+            if subtree.contains(&parent_id) {
+                return Err(bad_request_response(ApiError::new(
+                    "PARENT_CYCLE",
+                    "A task cannot be moved under itself or one of its subtasks",
+                )));
+            }
+            task_tree::subtree_height(pool, task_id).await.map_err(db_error)?
+        }
+        None => 1,
+    };
+
+    let parent_depth = task_tree::task_depth(pool, parent_id).await.map_err(db_error)?;
+    if parent_depth + subtree_height > MAX_TASK_DEPTH {
+        return Err(bad_request_response(ApiError::with_fields(
+            "MAX_DEPTH_EXCEEDED",
+            "Task hierarchy is too deep",
+            json!({ "max_depth": MAX_TASK_DEPTH }),
+        )));
+    }
+
+    Ok(())
+}
+
+// Only members of the task's workspace can be assigned
+async fn validate_assignee(pool: &PgPool, workspace_id: Uuid, assignee_id: Uuid) -> std::result::Result<(), HttpResponse> {
+    match access::member_role(pool, workspace_id, assignee_id).await {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(bad_request_response(ApiError::new(
+            "INVALID_ASSIGNEE",
+            "Assignee must be a member of the task's workspace",
+        ))),
+        Err(e) => Err(internal_error_response(&e.to_string())),
+    }
+}
+
+// Checks that the task can be filed under `project_id`
+async fn validate_project(pool: &PgPool, workspace_id: Uuid, project_id: Uuid) -> std::result::Result<(), HttpResponse> {
+    match project_in_workspace(pool, project_id, workspace_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(bad_request_response(ApiError::new("INVALID_PROJECT", "Project not found"))),
+        Err(e) => Err(internal_error_response(&e.to_string())),
+    }
+}
@@ -0,0 +1,522 @@
+use actix_web::{web, get, post, put, delete, HttpResponse, Result};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{
+    Workspace, WorkspaceMember, WorkspaceInvitation, WorkspaceRole, CreateWorkspaceRequest, UpdateWorkspaceRequest,
+    UpdateMemberRequest, CreateInvitationRequest, CreatedInvitation, AcceptInvitationRequest,
+};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::access::{self, Permission};
+use crate::services::notification_service::NotificationService;
+use crate::services::workspaces;
+use crate::utils::response::*;
+use crate::utils::tokens::{generate_token, hash_token};
+
+// How long an invitation token can be accepted
+const INVITATION_TTL_DAYS: i64 = 7;
+
+// Base SELECT for workspaces as seen by the user bound to $1
+const WORKSPACE_SELECT: &str = r#"
+    SELECT w.*, m.role,
+        (SELECT COUNT(*) FROM workspace_members c WHERE c.workspace_id = w.id) AS member_count
+    FROM workspaces w
+    JOIN workspace_members m ON m.workspace_id = w.id AND m.user_id = $1"#;
+
+const INVITATION_COLUMNS: &str = "id, workspace_id, email, role, invited_by, expires_at, accepted_at, created_at";
+
+async fn fetch_workspace(pool: &PgPool, workspace_id: Uuid, user_id: Uuid) -> Result<Option<Workspace>, sqlx::Error> {
+    sqlx::query_as::<_, Workspace>(&format!("{} WHERE w.id = $2", WORKSPACE_SELECT))
+        .bind(user_id)
+        .bind(workspace_id)
+        .fetch_optional(pool)
+        .await
+}
+
+fn workspace_response(workspace: Result<Option<Workspace>, sqlx::Error>, message: &str) -> HttpResponse {
+    match workspace {
+        Ok(Some(workspace)) => ok_response(workspace, message),
+        Ok(None) => not_found_response("workspace"),
+        Err(e) => internal_error_response(&e.to_string()),
+    }
+}
+
+fn personal_workspace_error(action: &str) -> HttpResponse {
+    let error = ApiError::new("PERSONAL_WORKSPACE", &format!("Personal workspaces cannot be {}", action));
+    bad_request_response(error)
+}
+
+#[get("")]
+pub async fn get_workspaces(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse> {
+    let workspaces = sqlx::query_as::<_, Workspace>(
+        &format!("{} ORDER BY w.personal DESC, w.created_at ASC", WORKSPACE_SELECT)
+    )
+    .bind(user.0.id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match workspaces {
+        Ok(workspaces) => Ok(ok_response(workspaces, "Workspaces retrieved successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to fetch workspaces: {}", e))),
+    }
+}
+
+#[post("")]
+pub async fn create_workspace(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    workspace_data: web::Json<CreateWorkspaceRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = workspace_data.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let workspace_id = match workspaces::create_workspace(pool.get_ref(), workspace_data.name.trim(), user.0.id).await {
+        Ok(workspace_id) => workspace_id,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to create workspace: {}", e))),
+    };
+
+    match fetch_workspace(pool.get_ref(), workspace_id, user.0.id).await {
+        Ok(Some(workspace)) => Ok(created_response(workspace, "Workspace created successfully")),
+        Ok(None) => Ok(not_found_response("workspace")),
+        Err(e) => Ok(internal_error_response(&e.to_string())),
+    }
+}
+
+#[get("/{id}")]
+pub async fn get_workspace(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let workspace = fetch_workspace(pool.get_ref(), path.into_inner(), user.0.id).await;
+    Ok(workspace_response(workspace, "Workspace retrieved successfully"))
+}
+
+#[put("/{id}")]
+pub async fn update_workspace(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    workspace_data: web::Json<UpdateWorkspaceRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = workspace_data.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let workspace_id = path.into_inner();
+
+    if let Err(e) = access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Administer).await {
+        return Ok(e.into_response("workspace"));
+    }
+
+    let result = sqlx::query("UPDATE workspaces SET name = $2 WHERE id = $1")
+        .bind(workspace_id)
+        .bind(workspace_data.name.trim())
+        .execute(pool.get_ref())
+        .await;
+
+    if let Err(e) = result {
+        return Ok(internal_error_response(&format!("Failed to update workspace: {}", e)));
+    }
+
+    let workspace = fetch_workspace(pool.get_ref(), workspace_id, user.0.id).await;
+    Ok(workspace_response(workspace, "Workspace updated successfully"))
+}
+
+#[delete("/{id}")]
+pub async fn delete_workspace(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let workspace_id = path.into_inner();
+
+    if let Err(e) = access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Own).await {
+        return Ok(e.into_response("workspace"));
+    }
+
+    // Tasks, projects, members and invitations go with it
+    let result = sqlx::query("DELETE FROM workspaces WHERE id = $1 AND NOT personal")
+        .bind(workspace_id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(rows) if rows.rows_affected() == 0 => Ok(personal_workspace_error("deleted")),
+        Ok(_) => Ok(ok_response(json!({"deleted": true}), "Workspace deleted successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to delete workspace: {}", e))),
+    }
+}
+
+#[get("/{id}/members")]
+pub async fn get_members(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let workspace_id = path.into_inner();
+
+    if let Err(e) = access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Read).await {
+        return Ok(e.into_response("workspace"));
+    }
+
+    let members = sqlx::query_as::<_, WorkspaceMember>(
+        r#"
+        SELECT u.id AS user_id, u.name, u.email, m.role, m.created_at AS joined_at
+        FROM workspace_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.workspace_id = $1
+        ORDER BY m.role DESC, u.name ASC
+        "#
+    )
+    .bind(workspace_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match members {
+        Ok(members) => Ok(ok_response(members, "Members retrieved successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to fetch members: {}", e))),
+    }
+}
+
+#[put("/{id}/members/{user_id}")]
+pub async fn update_member(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+    member_data: web::Json<UpdateMemberRequest>,
+) -> Result<HttpResponse> {
+    let (workspace_id, member_id) = path.into_inner();
+
+    let actor = match access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Administer).await {
+        Ok(access) => access.role,
+        Err(e) => return Ok(e.into_response("workspace")),
+    };
+
+    let current_role = match access::member_role(pool.get_ref(), workspace_id, member_id).await {
+        Ok(Some(role)) => role,
+        Ok(None) => return Ok(not_found_response("member")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    if !access::can_manage_role(actor, current_role) || !access::can_manage_role(actor, member_data.role) {
+        return Ok(forbidden_response("Your workspace role does not allow this action"));
+    }
+
+    let result = sqlx::query("UPDATE workspace_members SET role = $3 WHERE workspace_id = $1 AND user_id = $2")
+        .bind(workspace_id)
+        .bind(member_id)
+        .bind(member_data.role)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(_) => {
+            let data = json!({"user_id": member_id, "role": member_data.role});
+            Ok(ok_response(data, "Member updated successfully"))
+        }
+        Err(e) => Ok(internal_error_response(&format!("Failed to update member: {}", e))),
+    }
+}
+
+#[delete("/{id}/members/{user_id}")]
+pub async fn remove_member(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse> {
+    let (workspace_id, member_id) = path.into_inner();
+    let leaving = member_id == user.0.id;
+
+    // Anyone may leave; removing someone else takes an admin
+    let permission = if leaving { Permission::Read } else { Permission::Administer };
+    let actor = match access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, permission).await {
+        Ok(access) => access.role,
+        Err(e) => return Ok(e.into_response("workspace")),
+    };
+
+    let member_role = match access::member_role(pool.get_ref(), workspace_id, member_id).await {
+        Ok(Some(role)) => role,
+        Ok(None) => return Ok(not_found_response("member")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    if member_role == WorkspaceRole::Owner {
+        let error = ApiError::new("OWNER_CANNOT_LEAVE", "The workspace owner cannot be removed");
+        return Ok(bad_request_response(error));
+    }
+    if !leaving && !access::can_manage_role(actor, member_role) {
+        return Ok(forbidden_response("Your workspace role does not allow this action"));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let result = sqlx::query("DELETE FROM workspace_members WHERE workspace_id = $1 AND user_id = $2")
+        .bind(workspace_id)
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = result {
+        return Ok(internal_error_response(&format!("Failed to remove member: {}", e)));
+    }
+
+    // Former members keep no assignments in the workspace
+    let result = sqlx::query("UPDATE tasks SET assignee_id = NULL WHERE workspace_id = $1 AND assignee_id = $2")
+        .bind(workspace_id)
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = result {
+        return Ok(internal_error_response(&format!("Failed to remove member: {}", e)));
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(ok_response(json!({"deleted": true}), "Member removed successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to remove member: {}", e))),
+    }
+}
+
+#[post("/{id}/invitations")]
+pub async fn create_invitation(
+    pool: web::Data<PgPool>,
+    notification_service: web::Data<NotificationService>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    invitation_data: web::Json<CreateInvitationRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = invitation_data.validate() {
+        return Ok(validation_error_response(errors));
+    }
+
+    let workspace_id = path.into_inner();
+    let email = invitation_data.email.trim().to_lowercase();
+    let role = invitation_data.role.unwrap_or(WorkspaceRole::Member);
+
+    let actor = match access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Administer).await {
+        Ok(access) => access.role,
+        Err(e) => return Ok(e.into_response("workspace")),
+    };
+
+    if !access::can_manage_role(actor, role) {
+        return Ok(forbidden_response("Your workspace role does not allow this action"));
+    }
+
+    let workspace = match fetch_workspace(pool.get_ref(), workspace_id, user.0.id).await {
+        Ok(Some(workspace)) => workspace,
+        Ok(None) => return Ok(not_found_response("workspace")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    if workspace.personal {
+        return Ok(personal_workspace_error("shared"));
+    }
+
+    let already_member = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM workspace_members m JOIN users u ON u.id = m.user_id
+            WHERE m.workspace_id = $1 AND LOWER(u.email) = $2
+        )
+        "#
+    )
+    .bind(workspace_id)
+    .bind(&email)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match already_member {
+        Ok(false) => {}
+        Ok(true) => return Ok(conflict_response("User is already a member of this workspace")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    }
+
+    let token = generate_token();
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    // A new invitation replaces any pending one for the same address
+    let replaced = sqlx::query(
+        "DELETE FROM workspace_invitations WHERE workspace_id = $1 AND email = $2 AND accepted_at IS NULL"
+    )
+    .bind(workspace_id)
+    .bind(&email)
+    .execute(&mut *tx)
+    .await;
+
+    if let Err(e) = replaced {
+        return Ok(internal_error_response(&format!("Failed to create invitation: {}", e)));
+    }
+
+    let invitation = sqlx::query_as::<_, WorkspaceInvitation>(
+        &format!(
+            r#"
+            INSERT INTO workspace_invitations (workspace_id, email, role, token_hash, invited_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            INVITATION_COLUMNS
+        )
+    )
+    .bind(workspace_id)
+    .bind(&email)
+    .bind(role)
+    .bind(hash_token(&token))
+    .bind(user.0.id)
+    .bind(Utc::now() + Duration::days(INVITATION_TTL_DAYS))
+    .fetch_one(&mut *tx)
+    .await;
+
+    let invitation = match invitation {
+        Ok(invitation) => invitation,
+        Err(e) => return Ok(internal_error_response(&format!("Failed to create invitation: {}", e))),
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to create invitation: {}", e)));
+    }
+
+    // Invitees who already have an account hear about it right away
+    let invitee = sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE LOWER(email) = $1")
+        .bind(&email)
+        .fetch_optional(pool.get_ref())
+        .await;
+
+    if let Ok(Some(invitee_id)) = invitee {
+        let _ = notification_service.notify_workspace_invitation(
+            invitee_id,
+            &workspace.name,
+            workspace_id,
+            &user.0.name,
+        ).await;
+    }
+
+    Ok(created_response(CreatedInvitation { invitation, token }, "Invitation created successfully"))
+}
+
+#[get("/{id}/invitations")]
+pub async fn get_invitations(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let workspace_id = path.into_inner();
+
+    if let Err(e) = access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Administer).await {
+        return Ok(e.into_response("workspace"));
+    }
+
+    let invitations = sqlx::query_as::<_, WorkspaceInvitation>(
+        &format!(
+            "SELECT {} FROM workspace_invitations WHERE workspace_id = $1 AND accepted_at IS NULL ORDER BY created_at DESC",
+            INVITATION_COLUMNS
+        )
+    )
+    .bind(workspace_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match invitations {
+        Ok(invitations) => Ok(ok_response(invitations, "Invitations retrieved successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to fetch invitations: {}", e))),
+    }
+}
+
+#[delete("/{id}/invitations/{invitation_id}")]
+pub async fn revoke_invitation(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse> {
+    let (workspace_id, invitation_id) = path.into_inner();
+
+    if let Err(e) = access::authorize_workspace(pool.get_ref(), workspace_id, user.0.id, Permission::Administer).await {
+        return Ok(e.into_response("workspace"));
+    }
+
+    let result = sqlx::query(
+        "DELETE FROM workspace_invitations WHERE id = $1 AND workspace_id = $2 AND accepted_at IS NULL"
+    )
+    .bind(invitation_id)
+    .bind(workspace_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(rows) if rows.rows_affected() == 0 => Ok(not_found_response("invitation")),
+        Ok(_) => Ok(ok_response(json!({"deleted": true}), "Invitation revoked successfully")),
+        Err(e) => Ok(internal_error_response(&format!("Failed to revoke invitation: {}", e))),
+    }
+}
+
+#[post("/invitations/accept")]
+pub async fn accept_invitation(
+    pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    body: web::Json<AcceptInvitationRequest>,
+) -> Result<HttpResponse> {
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    // Invitations are bound to the invited address and can only be used once
+    let invitation = sqlx::query_as::<_, WorkspaceInvitation>(
+        &format!(
+            r#"
+            UPDATE workspace_invitations SET accepted_at = NOW()
+            WHERE token_hash = $1 AND LOWER(email) = LOWER($2) AND accepted_at IS NULL AND expires_at > NOW()
+            RETURNING {}
+            "#,
+            INVITATION_COLUMNS
+        )
+    )
+    .bind(hash_token(&body.token))
+    .bind(&user.0.email)
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let invitation = match invitation {
+        Ok(Some(invitation)) => invitation,
+        Ok(None) => return Ok(not_found_response("invitation")),
+        Err(e) => return Ok(internal_error_response(&e.to_string())),
+    };
+
+    let joined = sqlx::query(
+        r#"
+        INSERT INTO workspace_members (workspace_id, user_id, role)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (workspace_id, user_id) DO NOTHING
+        "#
+    )
+    .bind(invitation.workspace_id)
+    .bind(user.0.id)
+    .bind(invitation.role)
+    .execute(&mut *tx)
+    .await;
+
+    if let Err(e) = joined {
+        return Ok(internal_error_response(&format!("Failed to accept invitation: {}", e)));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error_response(&format!("Failed to accept invitation: {}", e)));
+    }
+
+    let workspace = fetch_workspace(pool.get_ref(), invitation.workspace_id, user.0.id).await;
+    Ok(workspace_response(workspace, "Invitation accepted successfully"))
+}
@@ -13,8 +13,14 @@ mod utils;
 mod services;
 
 use database::create_pool;
+use services::attachments::AttachmentLimits;
 use services::notification_service::NotificationService;
 use services::scheduler::TaskScheduler;
+use services::storage::storage_from_env;
+use services::mailer::mailer_from_env;
+use services::email_verifications::UnverifiedAccess;
+use services::trash;
+use services::accounts;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -67,9 +73,20 @@ async fn main() -> std::io::Result<()> {
     // Initialize notification service
     let notification_service = NotificationService::new(pool.clone());
     
+    // Attachment storage backend and upload limits
+    let storage = storage_from_env().expect("Invalid attachment storage configuration");
+    let attachment_limits = AttachmentLimits::from_env();
+
+    // How emails to users are delivered
+    let mailer = mailer_from_env().expect("Invalid mailer configuration");
+    let unverified_access = UnverifiedAccess::from_env().expect("Invalid UNVERIFIED_ACCESS");
+
     // Initialize and start task scheduler
     info!("Starting task scheduler...");
-    let scheduler = TaskScheduler::new(pool.clone(), notification_service.clone());
+    let scheduler = TaskScheduler::new(pool.clone(), notification_service.clone())
+        .with_storage(storage.clone())
+        .with_trash_retention(trash::retention_from_env())
+        .with_account_deletion_grace(accounts::deletion_grace_from_env());
     scheduler.start();
     
     info!("Starting server at {}:{}", server_host, server_port);
@@ -80,8 +97,9 @@ async fn main() -> std::io::Result<()> {
         } else {
             Cors::default()
                 .allowed_origin(&cors_origin)
-                .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
-                .allowed_headers(vec!["Content-Type", "Authorization"])
+                .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE"])
+                .allowed_headers(vec!["Content-Type", "Authorization", "Range", "If-Match", "If-None-Match"])
+                .expose_headers(vec!["Content-Range", "Content-Disposition", "ETag"])
                 .supports_credentials()
                 .max_age(3600)
         };
@@ -89,6 +107,10 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .app_data(web::Data::new(pool.clone()))
             .app_data(web::Data::new(notification_service.clone()))
+            .app_data(web::Data::from(storage.clone()))
+            .app_data(web::Data::from(mailer.clone()))
+            .app_data(web::Data::new(unverified_access))
+            .app_data(web::Data::new(attachment_limits))
             .wrap(cors)
             .wrap(Logger::default())
             .service(
@@ -98,16 +120,87 @@ async fn main() -> std::io::Result<()> {
                         web::scope("/auth")
                             .service(handlers::auth::register)
                             .service(handlers::auth::login)
+                            .service(handlers::auth::refresh)
+                            .service(handlers::auth::logout)
+                            .service(handlers::auth::verify_email)
+                            .service(handlers::auth::resend_verification)
+                            .service(handlers::auth::forgot_password)
+                            .service(handlers::auth::reset_password)
+                            .service(handlers::auth::get_sessions)
+                            .service(handlers::auth::revoke_session)
                             .service(handlers::auth::me)
+                            .service(handlers::auth::update_me)
+                            .service(handlers::auth::change_password)
+                            .service(handlers::auth::change_email)
+                            .service(handlers::auth::delete_me)
+                            .service(handlers::auth::get_settings)
+                            .service(handlers::auth::update_settings)
                     )
                     .service(
                         web::scope("/tasks")
+                            .app_data(handlers::tasks::merge_patch_json_config())
                             .service(handlers::tasks::get_tasks)
                             .service(handlers::tasks::create_task)
+                            .service(handlers::tasks::bulk_update_tasks)
+                            .service(handlers::tasks::get_trash)
                             .service(handlers::tasks::get_task)
+                            .service(handlers::tasks::get_task_children)
+                            .service(handlers::tasks::get_task_tree)
+                            .service(handlers::tasks::assign_task)
+                            .service(handlers::tasks::unassign_task)
+                            .service(handlers::task_dependencies::get_dependencies)
+                            .service(handlers::task_dependencies::add_dependency)
+                            .service(handlers::task_dependencies::remove_dependency)
+                            .service(handlers::task_comments::get_comments)
+                            .service(handlers::task_comments::create_comment)
+                            .service(handlers::task_comments::update_comment)
+                            .service(handlers::task_comments::delete_comment)
+                            .service(handlers::task_comments::get_comment_history)
+                            .service(handlers::task_attachments::get_attachments)
+                            .service(handlers::task_attachments::upload_attachment)
+                            .service(handlers::task_attachments::download_attachment)
+                            .service(handlers::task_attachments::delete_attachment)
+                            .service(handlers::task_events::get_task_history)
+                            .service(handlers::tasks::restore_task)
+                            .service(handlers::tasks::move_task)
+                            .service(handlers::tasks::archive_task)
+                            .service(handlers::tasks::unarchive_task)
+                            .service(handlers::tasks::purge_task)
                             .service(handlers::tasks::update_task)
+                            .service(handlers::tasks::patch_task)
                             .service(handlers::tasks::delete_task)
                     )
+                    .service(
+                        web::scope("/projects")
+                            .service(handlers::projects::get_projects)
+                            .service(handlers::projects::create_project)
+                            .service(handlers::projects::get_project)
+                            .service(handlers::projects::update_project)
+                            .service(handlers::projects::delete_project)
+                    )
+                    .service(
+                        web::scope("/workspaces")
+                            .service(handlers::workspaces::accept_invitation)
+                            .service(handlers::workspaces::get_workspaces)
+                            .service(handlers::workspaces::create_workspace)
+                            .service(handlers::workspaces::get_workspace)
+                            .service(handlers::workspaces::update_workspace)
+                            .service(handlers::workspaces::delete_workspace)
+                            .service(handlers::workspaces::get_members)
+                            .service(handlers::workspaces::update_member)
+                            .service(handlers::workspaces::remove_member)
+                            .service(handlers::workspaces::create_invitation)
+                            .service(handlers::workspaces::get_invitations)
+                            .service(handlers::workspaces::revoke_invitation)
+                    )
+                    .service(
+                        web::scope("/activity")
+                            .service(handlers::task_events::get_activity)
+                    )
+                    .service(
+                        web::scope("/mentions")
+                            .service(handlers::mentions::get_mentions)
+                    )
                     .service(
                         web::scope("/notifications")
                             .service(handlers::notifications::get_notifications)
@@ -1,17 +1,27 @@
-use actix_web::{web, Error, FromRequest};
-use actix_web::error::ErrorUnauthorized;
+use actix_web::{web, Error, FromRequest, HttpResponse};
+use actix_web::error::{ErrorUnauthorized, InternalError};
+use actix_web::http::header::HeaderValue;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::models::User;
+use crate::services::email_verifications::UnverifiedAccess;
+use crate::services::sessions;
+use crate::utils::response::ApiError;
 use crate::utils::jwt::verify_token;
 
 use actix_web::dev::Payload;
 use futures_util::future::LocalBoxFuture;
 
+// The user an access token was issued to. Users who haven't verified their
+// email only get as far as UnverifiedAccess allows.
 pub struct AuthenticatedUser(pub User);
 
-impl FromRequest for AuthenticatedUser {
+// The user and the session their access token was issued for, whether or not
+// their email is verified; for managing their own sessions and account
+pub struct SessionUser(pub User, pub Uuid);
+
+impl FromRequest for SessionUser {
     type Error = Error;
     type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
@@ -20,37 +30,90 @@ impl FromRequest for AuthenticatedUser {
         let pool = req.app_data::<web::Data<PgPool>>().cloned();
 
         Box::pin(async move {
-            let auth_header = auth_header
-                .ok_or_else(|| ErrorUnauthorized("Authorization header missing"))?;
-
-            let auth_str = auth_header
-                .to_str()
-                .map_err(|_| ErrorUnauthorized("Invalid authorization header format"))?;
-
-            if !auth_str.starts_with("Bearer ") {
-                return Err(ErrorUnauthorized("Authorization header must start with Bearer"));
-            }
+            let (user, session_id) = authenticate(auth_header, pool).await?;
+            Ok(SessionUser(user, session_id))
+        })
+    }
+}
 
-            let token = &auth_str[7..];
-            let claims = verify_token(token)?;
+impl FromRequest for AuthenticatedUser {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
-            let user_id = Uuid::parse_str(&claims.user_id)
-                .map_err(|_| ErrorUnauthorized("Invalid user ID in token"))?;
+    fn from_request(req: &actix_web::HttpRequest, _: &mut Payload) -> Self::Future {
+        let auth_header = req.headers().get("Authorization").cloned();
+        let pool = req.app_data::<web::Data<PgPool>>().cloned();
+        let access = UnverifiedAccess::of_app(req);
+        let read_only = req.method().is_safe();
 
-            let pool = pool
-                .ok_or_else(|| ErrorUnauthorized("Database pool not found"))?;
+        Box::pin(async move {
+            let (user, _) = authenticate(auth_header, pool).await?;
 
-            // Fetch user from database to ensure they still exist
-            let user = sqlx::query_as::<_, User>(
-                "SELECT * FROM users WHERE id = $1"
-            )
-            .bind(&user_id)
-            .fetch_optional(pool.get_ref())
-            .await
-            .map_err(|e| ErrorUnauthorized(format!("Database error: {}", e)))?
-            .ok_or_else(|| ErrorUnauthorized("User not found"))?;
+            let allowed = match access {
+                _ if user.email_verified_at.is_some() => true,
+                UnverifiedAccess::Full => true,
+                UnverifiedAccess::ReadOnly => read_only,
+                UnverifiedAccess::Blocked => false,
+            };
+            if !allowed {
+                return Err(InternalError::from_response(
+                    "Email address not verified",
+                    HttpResponse::Forbidden()
+                        .json(ApiError::new("EMAIL_NOT_VERIFIED", "Verify your email address to do this")),
+                )
+                .into());
+            }
 
             Ok(AuthenticatedUser(user))
         })
     }
-}
\ No newline at end of file
+}
+
+async fn authenticate(auth_header: Option<HeaderValue>, pool: Option<web::Data<PgPool>>) -> Result<(User, Uuid), Error> {
+    let auth_header = auth_header
+        .ok_or_else(|| ErrorUnauthorized("Authorization header missing"))?;
+
+    let auth_str = auth_header
+        .to_str()
+        .map_err(|_| ErrorUnauthorized("Invalid authorization header format"))?;
+
+    if !auth_str.starts_with("Bearer ") {
+        return Err(ErrorUnauthorized("Authorization header must start with Bearer"));
+    }
+
+    let token = &auth_str[7..];
+    let claims = verify_token(token)?;
+
+    let user_id = Uuid::parse_str(&claims.user_id)
+        .map_err(|_| ErrorUnauthorized("Invalid user ID in token"))?;
+    let session_id = Uuid::parse_str(&claims.sid)
+        .map_err(|_| ErrorUnauthorized("Invalid session ID in token"))?;
+
+    let pool = pool
+        .ok_or_else(|| ErrorUnauthorized("Database pool not found"))?;
+
+    // Fetch user from database to ensure they still exist, haven't deleted
+    // their account and haven't been logged out of the session, noting the
+    // session was seen unless that was done recently
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        WITH seen AS (
+            UPDATE sessions SET last_seen_at = NOW()
+            WHERE id = $2 AND user_id = $1 AND revoked_at IS NULL
+                AND last_seen_at < NOW() - make_interval(secs => $3)
+        )
+        SELECT u.* FROM users u
+        JOIN sessions s ON s.user_id = u.id
+        WHERE u.id = $1 AND s.id = $2 AND s.revoked_at IS NULL AND u.deleted_at IS NULL
+        "#
+    )
+    .bind(user_id)
+    .bind(session_id)
+    .bind(sessions::LAST_SEEN_INTERVAL_SECONDS)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|e| ErrorUnauthorized(format!("Database error: {}", e)))?
+    .ok_or_else(|| ErrorUnauthorized("Session not found or revoked"))?;
+
+    Ok((user, session_id))
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Mention {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    // None when the mention is in the task description
+    pub comment_id: Option<Uuid>,
+    pub mentioned_by: Option<Uuid>,
+    pub mentioned_by_name: Option<String>,
+    pub excerpt: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MentionQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+// Keyset page of mentions, newest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MentionsPage {
+    pub mentions: Vec<Mention>,
+    pub next_cursor: Option<String>,
+}
@@ -1,6 +1,24 @@
 pub mod user;
 pub mod task;
+pub mod task_filter;
 pub mod notification;
+pub mod task_dependency;
+pub mod task_comment;
+pub mod task_attachment;
+pub mod task_event;
+pub mod mention;
+pub mod project;
+pub mod workspace;
+pub mod session;
 
 pub use user::*;
-pub use task::*;
\ No newline at end of file
+pub use task::*;
+pub use task_filter::*;
+pub use task_dependency::*;
+pub use task_comment::*;
+pub use task_attachment::*;
+pub use task_event::*;
+pub use mention::*;
+pub use project::*;
+pub use workspace::*;
+pub use session::*;
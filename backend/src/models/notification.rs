@@ -36,6 +36,13 @@ pub struct NotificationResponse {
     pub metadata: Value,
 }
 
+// Keyset page of notifications, newest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationsPage {
+    pub notifications: Vec<NotificationResponse>,
+    pub next_cursor: Option<String>,
+}
+
 impl From<Notification> for NotificationResponse {
     fn from(notification: Notification) -> Self {
         Self {
@@ -67,4 +74,10 @@ pub enum WebSocketMessage {
     Ping,
     #[serde(rename = "pong")]
     Pong,
+    #[serde(rename = "auth")]
+    Auth { token: String },
+    #[serde(rename = "authenticated")]
+    Authenticated { status: String, user_id: Uuid, expires_at: i64 },
+    #[serde(rename = "error")]
+    Error { code: String, message: String },
 }
@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::utils::validation::validate_hex_color;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Project {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub archived: bool,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // Task progress, filled in by project reads
+    #[sqlx(default)]
+    #[serde(default)]
+    pub tasks_total: i64,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub tasks_done: i64,
+}
+
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct CreateProjectRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+    #[validate(custom(function = "validate_hex_color", message = "Color must be a #RRGGBB hex value"))]
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub sort_order: Option<i32>,
+    // Defaults to the user's personal workspace
+    pub workspace_id: Option<Uuid>,
+}
+
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct UpdateProjectRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+    #[validate(custom(function = "validate_hex_color", message = "Color must be a #RRGGBB hex value"))]
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub archived: Option<bool>,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectFilters {
+    // Archived projects are hidden unless requested
+    pub include_archived: Option<bool>,
+    pub workspace_id: Option<Uuid>,
+}
+
+// What happens to a project's tasks when it is deleted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectTasksOnDelete {
+    // Move them to `move_to`, or out of any project when it's omitted
+    #[default]
+    Move,
+    Delete,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteProjectQuery {
+    #[serde(default)]
+    pub tasks: ProjectTasksOnDelete,
+    pub move_to: Option<Uuid>,
+}
@@ -4,6 +4,8 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use validator::Validate;
 
+use super::task_filter::{AssigneeFilter, FilterValues, OrGroup, SearchLanguage, TagMatch};
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "task_status", rename_all = "lowercase")]
 pub enum TaskStatus {
@@ -38,9 +40,40 @@ pub struct Task {
     pub tags: serde_json::Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub parent_id: Option<Uuid>,
+    pub recurrence_rule: Option<String>,
+    pub recurrence_series_id: Option<Uuid>,
+    pub recurrence_index: Option<i32>,
+    pub project_id: Option<Uuid>,
+    pub workspace_id: Uuid,
+    pub assignee_id: Option<Uuid>,
+    // Set while the task is in the trash
+    pub deleted_at: Option<DateTime<Utc>>,
+    // Archived tasks are left out of task lists unless asked for
+    pub archived_at: Option<DateTime<Utc>>,
+    // When the task was last marked done
+    pub completed_at: Option<DateTime<Utc>>,
+    // Bumped on every change, so clients can tell a stale copy apart
+    pub version: i32,
+    // Order among tasks of the same status; lower comes first
+    pub position: f64,
+    // Subtask progress, computed when the task is read
+    #[sqlx(default)]
+    #[serde(default)]
+    pub subtasks_total: i64,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub subtasks_done: i64,
+    // Whether any task this one depends on is still open
+    #[sqlx(default)]
+    #[serde(default)]
+    pub is_blocked: bool,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub comment_count: i64,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Default, Deserialize, Validate)]
 pub struct CreateTaskRequest {
     #[validate(length(min = 3, max = 120, message = "Title must be between 3 and 120 characters"))]
     pub title: String,
@@ -50,9 +83,16 @@ pub struct CreateTaskRequest {
     pub priority: Option<TaskPriority>,
     pub due_date: Option<DateTime<Utc>>,
     pub tags: Option<Vec<String>>,
+    pub parent_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    // Defaults to the user's personal workspace
+    pub workspace_id: Option<Uuid>,
+    pub assignee_id: Option<Uuid>,
+    // RFC 5545 RRULE, e.g. "FREQ=WEEKLY;BYDAY=MO"
+    pub recurrence: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Default, Deserialize, Validate)]
 pub struct UpdateTaskRequest {
     #[validate(length(min = 3, max = 120, message = "Title must be between 3 and 120 characters"))]
     pub title: Option<String>,
@@ -62,29 +102,250 @@ pub struct UpdateTaskRequest {
     pub priority: Option<TaskPriority>,
     pub due_date: Option<DateTime<Utc>>,
     pub tags: Option<Vec<String>>,
+    pub parent_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    // RFC 5545 RRULE, e.g. "FREQ=WEEKLY;BYDAY=MO"
+    pub recurrence: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct TaskFilters {
+/// A JSON merge patch (RFC 7396) for a task: absent fields are left alone and
+/// `null` clears the nullable ones. Fields that can't be cleared reject `null`.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct PatchTaskRequest {
+    #[validate(length(min = 3, max = 120, message = "Title must be between 3 and 120 characters"))]
+    #[serde(default, deserialize_with = "non_null")]
+    pub title: Option<String>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    pub description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "non_null")]
     pub status: Option<TaskStatus>,
+    #[serde(default, deserialize_with = "non_null")]
     pub priority: Option<TaskPriority>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub due_date: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub tags: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub parent_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub project_id: Option<Option<Uuid>>,
+    // null stops the task recurring
+    #[serde(default, deserialize_with = "nullable")]
+    pub recurrence: Option<Option<String>>,
+}
+
+// A full update is a patch that never clears anything
+impl From<UpdateTaskRequest> for PatchTaskRequest {
+    fn from(request: UpdateTaskRequest) -> Self {
+        Self {
+            title: request.title,
+            description: request.description.map(Some),
+            status: request.status,
+            priority: request.priority,
+            due_date: request.due_date.map(Some),
+            tags: request.tags,
+            parent_id: request.parent_id.map(Some),
+            project_id: request.project_id.map(Some),
+            recurrence: request.recurrence.map(Some),
+        }
+    }
+}
+
+// Present fields, null included, become Some
+pub(crate) fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+fn non_null<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskFilters {
+    pub status: Option<FilterValues<TaskStatus>>,
+    #[serde(rename = "status!")]
+    pub status_not: Option<FilterValues<TaskStatus>>,
+    pub priority: Option<FilterValues<TaskPriority>>,
+    #[serde(rename = "priority!")]
+    pub priority_not: Option<FilterValues<TaskPriority>>,
+    // Substring match against any tag
     pub tag: Option<String>,
+    // Exact tags, matched according to tags_mode
+    pub tags: Option<FilterValues<String>>,
+    pub tags_mode: Option<TagMatch>,
+    #[serde(rename = "tags!")]
+    pub tags_not: Option<FilterValues<String>>,
+    pub or: Option<OrGroup>,
+    // Full-text query: supports "quoted phrases", OR and -excluded terms
     pub search: Option<String>,
+    pub search_lang: Option<SearchLanguage>,
     pub page: Option<u32>,
     pub limit: Option<u32>,
+    // Opaque keyset position from a previous next_cursor; takes precedence over page
+    pub cursor: Option<String>,
+    pub include_total: Option<bool>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
     pub created_from: Option<DateTime<Utc>>,
     pub created_to: Option<DateTime<Utc>>,
     pub due_from: Option<DateTime<Utc>>,
     pub due_to: Option<DateTime<Utc>>,
+    pub parent_id: Option<Uuid>,
+    pub root_only: Option<bool>,
+    pub project_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub assignee: Option<AssigneeFilter>,
+    pub blocked: Option<bool>,
+    // Archived tasks are hidden unless this is true
+    pub include_archived: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct TasksResponse {
     pub tasks: Vec<Task>,
-    pub total: i64,
+    // Omitted when requested with include_total=false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
     pub page: u32,
     pub limit: u32,
-    pub total_pages: u32,
-}
\ No newline at end of file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<u32>,
+    pub next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<Vec<TaskHighlight>>,
+}
+
+// Search snippets for a listed task, with matches wrapped in <mark> tags
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TaskHighlight {
+    pub task_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskTreeNode {
+    #[serde(flatten)]
+    pub task: Task,
+    pub children: Vec<TaskTreeNode>,
+}
+
+/// What happens to a task's children when it is deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChildrenOnDelete {
+    /// Children are kept and become root tasks
+    #[default]
+    Orphan,
+    /// The whole subtree is deleted
+    Delete,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteTaskQuery {
+    pub children: Option<ChildrenOnDelete>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrashQuery {
+    pub workspace_id: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrashPage {
+    pub tasks: Vec<Task>,
+    pub next_cursor: Option<String>,
+}
+
+/// Moves a task within its status column or to another one. Neighbours must
+/// be in the target column; without either the task goes last.
+#[derive(Debug, Deserialize)]
+pub struct MoveTaskRequest {
+    pub status: TaskStatus,
+    // The task that will come right before the moved one
+    pub before_id: Option<Uuid>,
+    // The task that will come right after it
+    pub after_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignTaskRequest {
+    // None unassigns the task
+    pub assignee_id: Option<Uuid>,
+}
+
+/// A change applied to every task of a bulk request.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    SetStatus { status: TaskStatus },
+    SetPriority { priority: TaskPriority },
+    AddTags { tags: Vec<String> },
+    RemoveTags { tags: Vec<String> },
+    /// A null project takes the tasks out of their project
+    MoveToProject { project_id: Option<Uuid> },
+    /// Moves the tasks to the trash; their children become root tasks
+    Delete,
+    Archive,
+}
+
+/// How a bulk request treats tasks that can't be changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkMode {
+    /// Any failure leaves every task as it was
+    #[default]
+    AllOrNothing,
+    /// The other tasks are changed anyway
+    PerItem,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkTaskRequest {
+    #[validate(length(min = 1, max = 100, message = "Between 1 and 100 tasks can be changed at once"))]
+    pub ids: Vec<Uuid>,
+    pub operation: BulkOperation,
+    #[serde(default)]
+    pub mode: BulkMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkItemStatus {
+    Updated,
+    // Already as asked, so nothing was written
+    Unchanged,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkItemError {
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkItemResult {
+    pub id: Uuid,
+    pub status: BulkItemStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<BulkItemError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkTaskResponse {
+    pub results: Vec<BulkItemResult>,
+    pub updated: usize,
+    pub failed: usize,
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TaskComment {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    // None once the author's account has been deleted
+    pub user_id: Option<Uuid>,
+    pub author_name: String,
+    pub reply_to: Option<Uuid>,
+    pub body: String,
+    // Set once the comment has been edited
+    pub edited_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// A previous version of an edited comment
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TaskCommentEdit {
+    pub id: Uuid,
+    pub comment_id: Uuid,
+    pub body: String,
+    pub edited_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCommentRequest {
+    #[validate(length(min = 1, max = 5000, message = "Comment must be between 1 and 5000 characters"))]
+    pub body: String,
+    pub reply_to: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCommentRequest {
+    #[validate(length(min = 1, max = 5000, message = "Comment must be between 1 and 5000 characters"))]
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+// Keyset page of comments, oldest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentsPage {
+    pub comments: Vec<TaskComment>,
+    pub next_cursor: Option<String>,
+}
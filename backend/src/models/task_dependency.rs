@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::Task;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TaskDependency {
+    pub task_id: Uuid,
+    pub blocked_by_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddDependencyRequest {
+    pub blocked_by_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskDependenciesResponse {
+    // Tasks that must be finished before this one
+    pub blocked_by: Vec<Task>,
+    // Tasks waiting on this one
+    pub blocking: Vec<Task>,
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::task_filter::FilterValues;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "task_event_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventType {
+    Created,
+    Updated,
+    StatusChanged,
+    // Moved to the trash
+    Deleted,
+    Restored,
+    // Removed for good, from the trash
+    Purged,
+    Archived,
+    Unarchived,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TaskEvent {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub workspace_id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub event_type: TaskEventType,
+    pub task_title: String,
+    // {"field": {"old": ..., "new": ...}} for each field that changed
+    pub changes: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TaskEventQuery {
+    #[serde(rename = "type")]
+    pub event_type: Option<FilterValues<TaskEventType>>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    // Only used by the activity feed
+    pub workspace_id: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskEventsPage {
+    pub events: Vec<TaskEvent>,
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,164 @@
+use serde::de::{self, Deserializer, IntoDeserializer};
+use serde::Deserialize;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use super::{TaskPriority, TaskStatus};
+
+/// Comma-separated list of values in a query string, e.g. `status=todo,doing`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterValues<T>(pub Vec<T>);
+
+impl<T> FromStr for FilterValues<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let values = value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                let deserializer: de::value::StrDeserializer<'_, de::value::Error> = item.into_deserializer();
+                T::deserialize(deserializer).map_err(|_| format!("Invalid filter value '{}'", item))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if values.is_empty() {
+            return Err("Filter needs at least one value".to_string());
+        }
+
+        Ok(Self(values))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for FilterValues<T>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Whether a task must carry any or all of the requested tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
+}
+
+/// Text search configuration used for the `search` filter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub enum SearchLanguage {
+    #[default]
+    #[serde(rename = "es")]
+    Spanish,
+    #[serde(rename = "en")]
+    English,
+}
+
+impl SearchLanguage {
+    // Postgres text search configuration name
+    pub fn config(self) -> &'static str {
+        match self {
+            Self::Spanish => "spanish",
+            Self::English => "english",
+        }
+    }
+}
+
+impl FromStr for SearchLanguage {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "es" => Ok(Self::Spanish),
+            "en" => Ok(Self::English),
+            other => Err(format!("Unsupported search language '{}'", other)),
+        }
+    }
+}
+
+/// Who a task must be assigned to: `me`, `none` or a user id.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssigneeFilter {
+    Me,
+    Unassigned,
+    User(Uuid),
+}
+
+impl FromStr for AssigneeFilter {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "me" => Ok(Self::Me),
+            "none" => Ok(Self::Unassigned),
+            other => other
+                .parse()
+                .map(Self::User)
+                .map_err(|_| format!("Invalid assignee '{}'", other)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AssigneeFilter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+/// A single condition on a task field, optionally negated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterCondition {
+    Status { values: Vec<TaskStatus>, negated: bool },
+    Priority { values: Vec<TaskPriority>, negated: bool },
+    Tags { values: Vec<String>, negated: bool },
+}
+
+/// Conditions of which at least one must hold, written as
+/// `field:values|field!:values`, e.g. `or=status:done|priority:high,med`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrGroup(pub Vec<FilterCondition>);
+
+impl FromStr for OrGroup {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let conditions = value
+            .split('|')
+            .map(|term| {
+                let (field, values) = term
+                    .split_once(':')
+                    .ok_or_else(|| format!("Invalid OR condition '{}'", term))?;
+                let (field, negated) = match field.trim().strip_suffix('!') {
+                    Some(field) => (field, true),
+                    None => (field.trim(), false),
+                };
+
+                match field {
+                    "status" => Ok(FilterCondition::Status { values: values.parse::<FilterValues<_>>()?.0, negated }),
+                    "priority" => Ok(FilterCondition::Priority { values: values.parse::<FilterValues<_>>()?.0, negated }),
+                    "tags" => Ok(FilterCondition::Tags { values: values.parse::<FilterValues<_>>()?.0, negated }),
+                    other => Err(format!("Unsupported OR field '{}'", other)),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self(conditions))
+    }
+}
+
+impl<'de> Deserialize<'de> for OrGroup {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,168 @@
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::WorkspaceRole;
+use crate::utils::response::*;
+
+// Workspaces of the user bound right after it, for `workspace_id IN (...)` filters
+pub const MEMBER_WORKSPACES: &str = "SELECT workspace_id FROM workspace_members WHERE user_id = ";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Permission {
+    // See the workspace, its tasks and projects
+    Read,
+    // Create, edit and delete tasks and projects
+    Write,
+    // Manage members, invitations and workspace settings
+    Administer,
+    // Delete the workspace
+    Own,
+}
+
+impl Permission {
+    fn required_role(self) -> WorkspaceRole {
+        match self {
+            Self::Read => WorkspaceRole::Viewer,
+            Self::Write => WorkspaceRole::Member,
+            Self::Administer => WorkspaceRole::Admin,
+            Self::Own => WorkspaceRole::Owner,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AccessError {
+    // Missing, or the user isn't a member; both look the same to the client
+    NotFound,
+    // A member whose role is too low
+    Forbidden,
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for AccessError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Database(error)
+    }
+}
+
+impl AccessError {
+    pub fn into_response(self, resource: &str) -> HttpResponse {
+        match self {
+            Self::NotFound => not_found_response(resource),
+            Self::Forbidden => forbidden_response("Your workspace role does not allow this action"),
+            Self::Database(e) => internal_error_response(&e.to_string()),
+        }
+    }
+}
+
+/// Workspace through which a user reaches a resource, and their role in it
+#[derive(Debug, Clone, Copy)]
+pub struct Access {
+    pub workspace_id: Uuid,
+    pub role: WorkspaceRole,
+}
+
+fn check(membership: Option<(Uuid, WorkspaceRole)>, permission: Permission) -> Result<Access, AccessError> {
+    let (workspace_id, role) = membership.ok_or(AccessError::NotFound)?;
+    if role < permission.required_role() {
+        return Err(AccessError::Forbidden);
+    }
+    Ok(Access { workspace_id, role })
+}
+
+/// Whether `actor` may grant or take away `role`. Admins manage members and
+/// viewers, only the owner manages admins, and ownership is never granted.
+pub fn can_manage_role(actor: WorkspaceRole, role: WorkspaceRole) -> bool {
+    match role {
+        WorkspaceRole::Owner => false,
+        WorkspaceRole::Admin => actor == WorkspaceRole::Owner,
+        WorkspaceRole::Member | WorkspaceRole::Viewer => actor >= WorkspaceRole::Admin,
+    }
+}
+
+pub async fn member_role(pool: &PgPool, workspace_id: Uuid, user_id: Uuid) -> Result<Option<WorkspaceRole>, sqlx::Error> {
+    sqlx::query_scalar::<_, WorkspaceRole>(
+        "SELECT role FROM workspace_members WHERE workspace_id = $1 AND user_id = $2"
+    )
+    .bind(workspace_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn authorize_workspace(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    user_id: Uuid,
+    permission: Permission,
+) -> Result<Access, AccessError> {
+    let role = member_role(pool, workspace_id, user_id).await?;
+    check(role.map(|role| (workspace_id, role)), permission)
+}
+
+/// Tasks in the trash are treated as missing; see `authorize_trashed_task`
+pub async fn authorize_task(
+    pool: &PgPool,
+    task_id: Uuid,
+    user_id: Uuid,
+    permission: Permission,
+) -> Result<Access, AccessError> {
+    task_membership(pool, task_id, user_id, false, permission).await
+}
+
+/// Like `authorize_task`, for tasks that are in the trash
+pub async fn authorize_trashed_task(
+    pool: &PgPool,
+    task_id: Uuid,
+    user_id: Uuid,
+    permission: Permission,
+) -> Result<Access, AccessError> {
+    task_membership(pool, task_id, user_id, true, permission).await
+}
+
+async fn task_membership(
+    pool: &PgPool,
+    task_id: Uuid,
+    user_id: Uuid,
+    trashed: bool,
+    permission: Permission,
+) -> Result<Access, AccessError> {
+    let membership = sqlx::query_as::<_, (Uuid, WorkspaceRole)>(
+        r#"
+        SELECT t.workspace_id, m.role
+        FROM tasks t
+        JOIN workspace_members m ON m.workspace_id = t.workspace_id AND m.user_id = $2
+        WHERE t.id = $1 AND (t.deleted_at IS NOT NULL) = $3
+        "#
+    )
+    .bind(task_id)
+    .bind(user_id)
+    .bind(trashed)
+    .fetch_optional(pool)
+    .await?;
+
+    check(membership, permission)
+}
+
+pub async fn authorize_project(
+    pool: &PgPool,
+    project_id: Uuid,
+    user_id: Uuid,
+    permission: Permission,
+) -> Result<Access, AccessError> {
+    let membership = sqlx::query_as::<_, (Uuid, WorkspaceRole)>(
+        r#"
+        SELECT p.workspace_id, m.role
+        FROM projects p
+        JOIN workspace_members m ON m.workspace_id = p.workspace_id AND m.user_id = $2
+        WHERE p.id = $1
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    check(membership, permission)
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use std::env;
+use uuid::Uuid;
+
+pub const DEFAULT_DELETION_GRACE_DAYS: i64 = 30;
+
+/// How long deleted accounts can still be recovered by logging in, from
+/// ACCOUNT_DELETION_GRACE_DAYS; 30 days for unset or invalid values
+pub fn deletion_grace_from_env() -> Duration {
+    let days = env::var("ACCOUNT_DELETION_GRACE_DAYS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|&days| days > 0)
+        .unwrap_or(DEFAULT_DELETION_GRACE_DAYS);
+
+    Duration::days(days)
+}
+
+/// Marks `user_id` as deleted and returns when; an account already marked
+/// keeps its original time
+pub async fn schedule_deletion(pool: &PgPool, user_id: Uuid) -> Result<DateTime<Utc>, sqlx::Error> {
+    sqlx::query_scalar::<_, DateTime<Utc>>(
+        "UPDATE users SET deleted_at = COALESCE(deleted_at, NOW()) WHERE id = $1 RETURNING deleted_at"
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Takes back the deletion of `user_id`; false if it wasn't deleted
+pub async fn cancel_deletion(pool: &PgPool, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("UPDATE users SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Workspaces other than the personal one where someone besides the owner is a member
+const OWNS_SHARED_WORKSPACE: &str = r#"
+    EXISTS (
+        SELECT 1 FROM workspaces w
+        JOIN workspace_members m ON m.workspace_id = w.id AND m.user_id <> w.owner_id
+        WHERE w.owner_id = $1 AND NOT w.personal
+    )"#;
+
+/// Whether `user_id` owns a workspace other people are members of; such
+/// accounts can't be deleted until the workspace is handed over or deleted
+pub async fn owns_shared_workspace(pool: &PgPool, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(&format!("SELECT {}", OWNS_SHARED_WORKSPACE))
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Deletes accounts that were deleted longer than `grace` ago, along with the
+/// workspaces they own. Tasks and projects they created in other people's
+/// workspaces are handed to that workspace's owner, and their comments there
+/// stay without an author; attachment blobs are queued for
+/// `attachments::purge_deleted_blobs`. Accounts are removed one at a time;
+/// those that fail to delete, or have come to own a shared workspace since,
+/// are tried again on the next purge. Returns how many were removed.
+pub async fn purge_deleted(pool: &PgPool, grace: Duration) -> Result<u64, sqlx::Error> {
+    let cutoff = Utc::now() - grace;
+    let expired = sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE deleted_at < $1")
+        .bind(cutoff)
+        .fetch_all(pool)
+        .await?;
+
+    let mut purged = 0;
+    for user_id in expired {
+        match purge_account(pool, user_id, cutoff).await {
+            Ok(true) => purged += 1,
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to purge deleted account {}: {}", user_id, e),
+        }
+    }
+
+    Ok(purged)
+}
+
+async fn purge_account(pool: &PgPool, user_id: Uuid, cutoff: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    for table in ["tasks", "projects"] {
+        sqlx::query(&format!(
+            r#"
+            UPDATE {} t SET user_id = w.owner_id
+            FROM workspaces w
+            WHERE w.id = t.workspace_id AND t.user_id = $1 AND w.owner_id <> $1
+            "#,
+            table
+        ))
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Still deleted, unless the user logged back in meanwhile
+    let result = sqlx::query(&format!(
+        "DELETE FROM users WHERE id = $1 AND deleted_at < $2 AND NOT {}",
+        OWNS_SHARED_WORKSPACE
+    ))
+    .bind(user_id)
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        tx.rollback().await?;
+        return Ok(false);
+    }
+
+    tx.commit().await?;
+    Ok(true)
+}
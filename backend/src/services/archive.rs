@@ -0,0 +1,41 @@
+use serde_json::Map;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::TaskEventType;
+use crate::services::task_events;
+
+/// Archives done tasks that were completed longer ago than their owner's
+/// `auto_archive_days`. Tasks unarchived by hand since they were completed
+/// are left alone. Returns how many were archived.
+pub async fn archive_finished(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let archived = sqlx::query_as::<_, (Uuid, Uuid, String)>(
+        r#"
+        UPDATE tasks t SET archived_at = NOW()
+        FROM users u
+        WHERE u.id = t.user_id
+            AND t.status = 'done'
+            AND t.archived_at IS NULL
+            AND t.deleted_at IS NULL
+            AND t.completed_at < NOW() - make_interval(days => u.auto_archive_days)
+            AND NOT EXISTS (
+                SELECT 1 FROM task_events e
+                WHERE e.task_id = t.id AND e.event_type = 'unarchived' AND e.created_at > t.completed_at
+            )
+        RETURNING t.id, t.workspace_id, t.title
+        "#
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (task_id, workspace_id, title) in &archived {
+        task_events::record(
+            &mut *tx, *task_id, *workspace_id, title, None, TaskEventType::Archived, Map::new(),
+        ).await?;
+    }
+
+    tx.commit().await?;
+    Ok(archived.len())
+}
@@ -0,0 +1,86 @@
+use sqlx::PgPool;
+use std::env;
+use uuid::Uuid;
+
+use crate::services::storage::StorageBackend;
+
+const DEFAULT_MAX_FILE_BYTES: i64 = 10 * 1024 * 1024;
+const DEFAULT_USER_QUOTA_BYTES: i64 = 100 * 1024 * 1024;
+
+// Queued blobs removed per purge
+const PURGE_BATCH_SIZE: i64 = 500;
+
+/// Upload size limits: per file, and in total across a user's uploads
+#[derive(Debug, Clone, Copy)]
+pub struct AttachmentLimits {
+    pub max_file_bytes: i64,
+    pub user_quota_bytes: i64,
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            user_quota_bytes: DEFAULT_USER_QUOTA_BYTES,
+        }
+    }
+}
+
+impl AttachmentLimits {
+    /// Reads ATTACHMENT_MAX_FILE_BYTES and ATTACHMENT_USER_QUOTA_BYTES, keeping
+    /// the defaults (10 MiB and 100 MiB) for unset or invalid values
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: i64| {
+            env::var(name)
+                .ok()
+                .and_then(|value| value.parse::<i64>().ok())
+                .filter(|&value| value > 0)
+                .unwrap_or(default)
+        };
+
+        Self {
+            max_file_bytes: read("ATTACHMENT_MAX_FILE_BYTES", DEFAULT_MAX_FILE_BYTES),
+            user_quota_bytes: read("ATTACHMENT_USER_QUOTA_BYTES", DEFAULT_USER_QUOTA_BYTES),
+        }
+    }
+}
+
+/// Storage key for a new attachment's blob
+pub fn storage_key(task_id: Uuid, attachment_id: Uuid) -> String {
+    format!("tasks/{}/{}", task_id, attachment_id)
+}
+
+/// Removes the blobs of deleted attachments from storage. Rows queue their
+/// blob when deleted, also through task, project or workspace cascades;
+/// blobs that fail to delete stay queued for the next purge.
+pub async fn purge_deleted_blobs(pool: &PgPool, storage: &dyn StorageBackend) -> Result<usize, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let keys = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT storage_key FROM attachment_blob_deletions
+        ORDER BY queued_at
+        LIMIT $1
+        FOR UPDATE SKIP LOCKED
+        "#
+    )
+    .bind(PURGE_BATCH_SIZE)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut deleted = Vec::with_capacity(keys.len());
+    for key in keys {
+        match storage.delete(&key).await {
+            Ok(()) => deleted.push(key),
+            Err(e) => tracing::warn!("Failed to delete attachment blob {}: {}", key, e),
+        }
+    }
+
+    sqlx::query("DELETE FROM attachment_blob_deletions WHERE storage_key = ANY($1)")
+        .bind(&deleted)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(deleted.len())
+}
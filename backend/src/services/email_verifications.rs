@@ -0,0 +1,141 @@
+use actix_web::{web, HttpRequest};
+use chrono::{Duration, Utc};
+use sqlx::{PgConnection, PgPool};
+use std::env;
+use uuid::Uuid;
+
+use crate::utils::tokens::{generate_token, hash_token};
+
+// How long a verification link works
+pub const TOKEN_TTL_HOURS: i64 = 24;
+// Links of the same kind for the same account are sent at most this often,
+// and at most MAX_PER_HOUR of either kind an hour
+pub const SEND_INTERVAL_SECONDS: f64 = 60.0;
+pub const MAX_PER_HOUR: i64 = 5;
+
+/// What users who haven't verified their email may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnverifiedAccess {
+    // Everything verified users can
+    Full,
+    // Only read; changes are refused until they verify
+    ReadOnly,
+    // Nothing; they can't log in
+    Blocked,
+}
+
+impl UnverifiedAccess {
+    /// From UNVERIFIED_ACCESS (full|read_only|blocked), full if unset. Other
+    /// values are an error rather than quietly opening the app up.
+    pub fn from_env() -> Result<Self, String> {
+        let access = env::var("UNVERIFIED_ACCESS").ok().filter(|value| !value.is_empty());
+
+        match access.map(|value| value.to_lowercase()).as_deref() {
+            None | Some("full") => Ok(UnverifiedAccess::Full),
+            Some("read_only") => Ok(UnverifiedAccess::ReadOnly),
+            Some("blocked") => Ok(UnverifiedAccess::Blocked),
+            Some(other) => Err(format!("Unknown UNVERIFIED_ACCESS '{}', expected full, read_only or blocked", other)),
+        }
+    }
+
+    /// The policy the app was configured with; apps that don't configure one
+    /// let unverified users do everything
+    pub fn of_app(req: &HttpRequest) -> Self {
+        req.app_data::<web::Data<UnverifiedAccess>>()
+            .map_or(UnverifiedAccess::Full, |access| *access.get_ref())
+    }
+}
+
+/// Creates a token verifying `email` for `user_id` and returns it, or None
+/// when the account has been sent links too recently or too often
+pub async fn issue(pool: &PgPool, user_id: Uuid, email: &str) -> Result<Option<String>, sqlx::Error> {
+    insert(pool, user_id, email, false).await
+}
+
+/// Like `issue`, for a token that moves `user_id` to `new_email` once
+/// followed. Links sent for earlier changes stop working.
+pub async fn issue_email_change(pool: &PgPool, user_id: Uuid, new_email: &str) -> Result<Option<String>, sqlx::Error> {
+    let token = insert(pool, user_id, new_email, true).await?;
+
+    if let Some(token) = &token {
+        sqlx::query(
+            r#"
+            UPDATE email_verifications SET used_at = NOW()
+            WHERE user_id = $1 AND changes_email AND used_at IS NULL AND token_hash <> $2
+            "#
+        )
+        .bind(user_id)
+        .bind(hash_token(token))
+        .execute(pool)
+        .await?;
+    }
+
+    Ok(token)
+}
+
+async fn insert(pool: &PgPool, user_id: Uuid, email: &str, changes_email: bool) -> Result<Option<String>, sqlx::Error> {
+    let token = generate_token();
+
+    let issued = sqlx::query(
+        r#"
+        INSERT INTO email_verifications (user_id, email, token_hash, expires_at, changes_email)
+        SELECT $1, $2, $3, $4, $7
+        WHERE NOT EXISTS (
+            SELECT 1 FROM email_verifications
+            WHERE user_id = $1 AND changes_email = $7 AND created_at > NOW() - make_interval(secs => $5)
+        )
+        AND (
+            SELECT COUNT(*) FROM email_verifications
+            WHERE user_id = $1 AND created_at > NOW() - INTERVAL '1 hour'
+        ) < $6
+        "#
+    )
+    .bind(user_id)
+    .bind(email)
+    .bind(hash_token(&token))
+    .bind(Utc::now() + Duration::hours(TOKEN_TTL_HOURS))
+    .bind(SEND_INTERVAL_SECONDS)
+    .bind(MAX_PER_HOUR)
+    .bind(changes_email)
+    .execute(pool)
+    .await?;
+
+    Ok((issued.rows_affected() > 0).then_some(token))
+}
+
+/// Uses up `token` and marks the address it was sent to as verified, as long
+/// as it is still the user's; email change tokens make it the user's address
+/// first. Returns the user's id; None if the token is unknown, expired, already
+/// used, for an address the user has moved away from, or for a new address
+/// someone else has taken since.
+pub async fn verify(conn: &mut PgConnection, token: &str) -> Result<Option<Uuid>, sqlx::Error> {
+    let verification = sqlx::query_as::<_, (Uuid, String, bool)>(
+        r#"
+        UPDATE email_verifications SET used_at = NOW()
+        WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+        RETURNING user_id, email, changes_email
+        "#
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some((user_id, email, changes_email)) = verification else {
+        return Ok(None);
+    };
+
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        UPDATE users SET
+            email_verified_at = CASE WHEN email = $2 THEN COALESCE(email_verified_at, NOW()) ELSE NOW() END,
+            email = $2
+        WHERE id = $1 AND (email = $2 OR ($3 AND NOT EXISTS (SELECT 1 FROM users WHERE email = $2)))
+        RETURNING id
+        "#
+    )
+    .bind(user_id)
+    .bind(&email)
+    .bind(changes_email)
+    .fetch_optional(&mut *conn)
+    .await
+}
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use std::path::PathBuf;
+use tokio::fs;
+use uuid::Uuid;
+
+use super::{Email, MailError, Mailer};
+
+/// Writes each email to its own file under a directory instead of sending it
+pub struct FileMailer {
+    root: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        fs::create_dir_all(&self.root).await?;
+
+        // Named so a directory listing sorts oldest first
+        let name = format!("{}-{}.eml", Utc::now().format("%Y%m%dT%H%M%S%.6f"), Uuid::new_v4());
+        let contents = format!("To: {}\nSubject: {}\n\n{}\n", email.to, email.subject, email.body);
+        fs::write(self.root.join(name), contents).await?;
+        Ok(())
+    }
+}
+
+/// Only logs emails; the default, for development
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        tracing::info!("Email to {}: {}\n{}", email.to, email.subject, email.body);
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use std::env;
+use std::sync::Arc;
+
+pub mod file;
+pub mod smtp;
+
+pub use file::{FileMailer, LogMailer};
+pub use smtp::{SmtpMailer, SmtpSecurity};
+
+/// A plain-text message to one recipient
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailError {
+    #[error("invalid address: {0}")]
+    Address(String),
+    #[error("mail I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("mail transport error: {0}")]
+    Transport(String),
+}
+
+/// How emails to users are delivered
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &Email) -> Result<(), MailError>;
+}
+
+/// Base URL of the web app, for links in emails, from APP_URL
+pub fn app_url() -> String {
+    env::var("APP_URL")
+        .unwrap_or_else(|_| "http://localhost:5173".to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Sends `email` in the background, so responses neither wait on delivery
+/// nor take longer for some recipients than others. Failures are only logged.
+pub fn send_later(mailer: Arc<dyn Mailer>, email: Email) {
+    actix_web::rt::spawn(async move {
+        if let Err(e) = mailer.send(&email).await {
+            tracing::error!("Failed to send email to {}: {}", email.to, e);
+        }
+    });
+}
+
+/// Builds the mailer selected by MAILER (log|file|smtp), log if unset
+pub fn mailer_from_env() -> Result<Arc<dyn Mailer>, String> {
+    let mailer = env::var("MAILER").unwrap_or_else(|_| "log".to_string());
+
+    match mailer.to_lowercase().as_str() {
+        "log" => Ok(Arc::new(LogMailer)),
+        "file" => {
+            let root = env::var("MAIL_FILE_PATH").unwrap_or_else(|_| "./data/mail".to_string());
+            Ok(Arc::new(FileMailer::new(root)))
+        }
+        "smtp" => {
+            // Empty values count as unset
+            let optional = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+            let var = |name: &str| optional(name).ok_or_else(|| format!("{} must be set for the smtp mailer", name));
+            let security = match optional("SMTP_SECURITY").unwrap_or_else(|| "starttls".to_string()).to_lowercase().as_str() {
+                "starttls" => SmtpSecurity::StartTls,
+                "tls" => SmtpSecurity::Tls,
+                "none" => SmtpSecurity::None,
+                other => return Err(format!("Unknown SMTP_SECURITY '{}', expected starttls, tls or none", other)),
+            };
+            let port = match optional("SMTP_PORT") {
+                Some(port) => Some(port.parse::<u16>().map_err(|_| format!("Invalid SMTP_PORT '{}'", port))?),
+                None => None,
+            };
+            let credentials = optional("SMTP_USERNAME").zip(optional("SMTP_PASSWORD"));
+            let mailer = SmtpMailer::new(&var("SMTP_HOST")?, port, security, credentials, &var("MAIL_FROM")?)
+                .map_err(|e| e.to_string())?;
+            Ok(Arc::new(mailer))
+        }
+        other => Err(format!("Unknown MAILER '{}', expected log, file or smtp", other)),
+    }
+}
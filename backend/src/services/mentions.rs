@@ -0,0 +1,116 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::User;
+use crate::services::notification_service::NotificationService;
+use crate::utils::mentions::{excerpt, extract_mentions};
+
+/// Stores the mentions in `text`, written by `author` in a task description
+/// (`comment_id` None) or comment, and notifies users mentioned there for the
+/// first time. Mentions no longer in the text are dropped; ones that remain
+/// aren't notified again.
+pub async fn sync_mentions(
+    pool: &PgPool,
+    notification_service: &NotificationService,
+    task_id: Uuid,
+    comment_id: Option<Uuid>,
+    text: &str,
+    author: &User,
+) -> Result<(), sqlx::Error> {
+    let handles = extract_mentions(text);
+    let names: Vec<String> = handles.iter().map(|m| m.handle.clone()).collect();
+
+    let task_title = sqlx::query_scalar::<_, String>("SELECT title FROM tasks WHERE id = $1")
+        .bind(task_id)
+        .fetch_one(pool)
+        .await?;
+
+    // Handles match the email's local part or the name without spaces, and
+    // only resolve to members of the task's workspace
+    let matches = sqlx::query_as::<_, (Uuid, String)>(
+        r#"
+        SELECT u.id, h.handle
+        FROM UNNEST($2::text[]) WITH ORDINALITY AS h(handle, ord)
+        JOIN users u
+            ON LOWER(split_part(u.email, '@', 1)) = h.handle
+            OR LOWER(REPLACE(u.name, ' ', '')) = h.handle
+        JOIN workspace_members m ON m.user_id = u.id
+        JOIN tasks t ON t.workspace_id = m.workspace_id AND t.id = $1
+        WHERE u.id <> $3
+        ORDER BY h.ord
+        "#
+    )
+    .bind(task_id)
+    .bind(&names)
+    .bind(author.id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut mentioned: Vec<(Uuid, String)> = Vec::new();
+    for (user_id, handle) in matches {
+        if mentioned.iter().any(|(id, _)| *id == user_id) {
+            continue;
+        }
+        let position = handles.iter().find(|m| m.handle == handle).map_or(0, |m| m.position);
+        mentioned.push((user_id, excerpt(text, position)));
+    }
+    let user_ids: Vec<Uuid> = mentioned.iter().map(|(id, _)| *id).collect();
+
+    let conflict_target = match comment_id {
+        Some(_) => "(comment_id, user_id) WHERE comment_id IS NOT NULL",
+        None => "(task_id, user_id) WHERE comment_id IS NULL",
+    };
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "DELETE FROM mentions WHERE task_id = $1 AND comment_id IS NOT DISTINCT FROM $2 AND user_id <> ALL($3)"
+    )
+    .bind(task_id)
+    .bind(comment_id)
+    .bind(&user_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    let mut new_mentions = Vec::new();
+    for (user_id, excerpt) in &mentioned {
+        // xmax is 0 only for freshly inserted rows
+        let inserted = sqlx::query_scalar::<_, bool>(
+            &format!(
+                r#"
+                INSERT INTO mentions (user_id, task_id, comment_id, mentioned_by, excerpt)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT {} DO UPDATE SET excerpt = EXCLUDED.excerpt
+                RETURNING (xmax = 0)
+                "#,
+                conflict_target
+            )
+        )
+        .bind(user_id)
+        .bind(task_id)
+        .bind(comment_id)
+        .bind(author.id)
+        .bind(excerpt)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if inserted {
+            new_mentions.push((*user_id, excerpt));
+        }
+    }
+
+    tx.commit().await?;
+
+    for (user_id, excerpt) in new_mentions {
+        let _ = notification_service.notify_mentioned(
+            user_id,
+            &task_title,
+            task_id,
+            comment_id,
+            &author.name,
+            excerpt,
+        ).await;
+    }
+
+    Ok(())
+}
@@ -1,2 +1,19 @@
 pub mod notification_service;
 pub mod scheduler;
+pub mod task_tree;
+pub mod task_position;
+pub mod task_dependencies;
+pub mod task_recurrence;
+pub mod access;
+pub mod workspaces;
+pub mod mentions;
+pub mod storage;
+pub mod attachments;
+pub mod task_events;
+pub mod trash;
+pub mod archive;
+pub mod sessions;
+pub mod mailer;
+pub mod password_resets;
+pub mod email_verifications;
+pub mod accounts;
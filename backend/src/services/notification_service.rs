@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{broadcast, RwLock};
 use actix_web_actors::ws;
 use actix::prelude::*;
@@ -9,8 +10,11 @@ use sqlx::{PgPool, Row};
 use uuid::Uuid;
 
 use crate::models::notification::{
-    CreateNotification, NotificationResponse, WebSocketMessage
+    CreateNotification, Notification, NotificationResponse, NotificationsPage, WebSocketMessage
 };
+use crate::services::sessions;
+use crate::utils::cursor;
+use crate::utils::jwt::{verify_token, Claims};
 use crate::utils::response::ApiError;
 
 // WebSocket connection manager
@@ -34,7 +38,6 @@ impl NotificationService {
         }
     }
 
-    #[allow(dead_code)]
     pub async fn create_notification(&self, notification: CreateNotification) -> Result<NotificationResponse, ApiError> {
         let metadata_value = notification.metadata.unwrap_or(Value::Object(serde_json::Map::new()));
         
@@ -80,7 +83,7 @@ impl NotificationService {
             SELECT id, user_id, notification_type, title, message, read_at, created_at, metadata
             FROM notifications
             WHERE user_id = $1
-            ORDER BY created_at DESC
+            ORDER BY created_at DESC, id DESC
             LIMIT $2 OFFSET $3
             "#,
         )
@@ -104,6 +107,38 @@ impl NotificationService {
         Ok(response_notifications)
     }
 
+    // Keyset variant of get_user_notifications; an empty cursor starts from the newest
+    pub async fn get_user_notifications_page(&self, user_id: Uuid, limit: Option<i64>, cursor: &str) -> Result<NotificationsPage, ApiError> {
+        let limit = limit.unwrap_or(50).clamp(1, 100);
+        let position = cursor::position::<i32>(Some(cursor))
+            .map_err(|message| ApiError::new("INVALID_CURSOR", &message))?;
+
+        let mut notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT id, user_id, notification_type, title, message, read_at, created_at, metadata
+            FROM notifications
+            WHERE user_id = $1
+              AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(position.as_ref().map(|p| p.at))
+        .bind(position.as_ref().map(|p| p.id))
+        .bind(limit + 1)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::new("DATABASE_ERROR", &e.to_string()))?;
+
+        let next_cursor = cursor::next_page(&mut notifications, limit, |last| Some((last.created_at, last.id)));
+
+        Ok(NotificationsPage {
+            notifications: notifications.into_iter().map(NotificationResponse::from).collect(),
+            next_cursor,
+        })
+    }
+
     pub async fn mark_as_read(&self, notification_ids: Vec<i32>, user_id: Uuid) -> Result<(), ApiError> {
         sqlx::query(
             r#"
@@ -154,6 +189,17 @@ impl NotificationService {
         }
     }
 
+    /// Closes `user_id`'s sockets that were opened with one of `session_ids`,
+    /// once those sessions have been revoked
+    pub async fn close_sessions(&self, user_id: Uuid, session_ids: &[Uuid]) {
+        let connections = self.connections.read().await;
+        if let Some(user_connections) = connections.get(&user_id) {
+            for addr in user_connections {
+                addr.do_send(SessionsRevoked(session_ids.to_vec()));
+            }
+        }
+    }
+
     pub fn get_broadcast_receiver(&self) -> broadcast::Receiver<(Uuid, NotificationResponse)> {
         self.broadcast_tx.subscribe()
     }
@@ -174,8 +220,7 @@ impl NotificationService {
         self.create_notification(notification).await
     }
 
-    #[allow(dead_code)]
-    pub async fn notify_task_assigned(&self, user_id: Uuid, task_title: &str, task_id: i32) -> Result<NotificationResponse, ApiError> {
+    pub async fn notify_task_assigned(&self, user_id: Uuid, task_title: &str, task_id: Uuid) -> Result<NotificationResponse, ApiError> {
         let notification = CreateNotification {
             user_id,
             notification_type: "task_assigned".to_string(),
@@ -189,8 +234,21 @@ impl NotificationService {
         self.create_notification(notification).await
     }
 
-    #[allow(dead_code)]
-    pub async fn notify_task_completed(&self, user_id: Uuid, task_title: &str, task_id: i32) -> Result<NotificationResponse, ApiError> {
+    pub async fn notify_task_unassigned(&self, user_id: Uuid, task_title: &str, task_id: Uuid) -> Result<NotificationResponse, ApiError> {
+        let notification = CreateNotification {
+            user_id,
+            notification_type: "task_unassigned".to_string(),
+            title: "Task Unassigned".to_string(),
+            message: format!("'{}' is no longer assigned to you", task_title),
+            metadata: Some(serde_json::json!({
+                "task_id": task_id
+            })),
+        };
+
+        self.create_notification(notification).await
+    }
+
+    pub async fn notify_task_completed(&self, user_id: Uuid, task_title: &str, task_id: Uuid) -> Result<NotificationResponse, ApiError> {
         let notification = CreateNotification {
             user_id,
             notification_type: "task_completed".to_string(),
@@ -204,6 +262,100 @@ impl NotificationService {
         self.create_notification(notification).await
     }
 
+    // One notification for many tasks completed at once, e.g. by a bulk update
+    pub async fn notify_tasks_completed(&self, user_id: Uuid, task_ids: &[Uuid]) -> Result<NotificationResponse, ApiError> {
+        let notification = CreateNotification {
+            user_id,
+            notification_type: "tasks_completed".to_string(),
+            title: "Tasks Completed".to_string(),
+            message: match task_ids.len() {
+                1 => "1 task has been marked as completed".to_string(),
+                count => format!("{} tasks have been marked as completed", count),
+            },
+            metadata: Some(serde_json::json!({
+                "task_ids": task_ids
+            })),
+        };
+
+        self.create_notification(notification).await
+    }
+
+    pub async fn notify_task_unblocked(&self, user_id: Uuid, task_title: &str, task_id: Uuid, blocker_title: &str) -> Result<NotificationResponse, ApiError> {
+        let notification = CreateNotification {
+            user_id,
+            notification_type: "task_unblocked".to_string(),
+            title: "Task Unblocked".to_string(),
+            message: format!("'{}' is no longer blocked: '{}' was completed", task_title, blocker_title),
+            metadata: Some(serde_json::json!({
+                "task_id": task_id
+            })),
+        };
+
+        self.create_notification(notification).await
+    }
+
+    pub async fn notify_tasks_unblocked(&self, user_id: Uuid, task_ids: &[Uuid]) -> Result<NotificationResponse, ApiError> {
+        let notification = CreateNotification {
+            user_id,
+            notification_type: "tasks_unblocked".to_string(),
+            title: "Tasks Unblocked".to_string(),
+            message: match task_ids.len() {
+                1 => "1 task is no longer blocked".to_string(),
+                count => format!("{} tasks are no longer blocked", count),
+            },
+            metadata: Some(serde_json::json!({
+                "task_ids": task_ids
+            })),
+        };
+
+        self.create_notification(notification).await
+    }
+
+    pub async fn notify_task_commented(&self, user_id: Uuid, task_title: &str, task_id: Uuid, comment_id: Uuid, author_name: &str) -> Result<NotificationResponse, ApiError> {
+        let notification = CreateNotification {
+            user_id,
+            notification_type: "task_commented".to_string(),
+            title: "New Comment".to_string(),
+            message: format!("{} commented on '{}'", author_name, task_title),
+            metadata: Some(serde_json::json!({
+                "task_id": task_id,
+                "comment_id": comment_id
+            })),
+        };
+
+        self.create_notification(notification).await
+    }
+
+    pub async fn notify_mentioned(&self, user_id: Uuid, task_title: &str, task_id: Uuid, comment_id: Option<Uuid>, author_name: &str, excerpt: &str) -> Result<NotificationResponse, ApiError> {
+        let notification = CreateNotification {
+            user_id,
+            notification_type: "mentioned".to_string(),
+            title: "You Were Mentioned".to_string(),
+            message: format!("{} mentioned you in '{}'", author_name, task_title),
+            metadata: Some(serde_json::json!({
+                "task_id": task_id,
+                "comment_id": comment_id,
+                "excerpt": excerpt
+            })),
+        };
+
+        self.create_notification(notification).await
+    }
+
+    pub async fn notify_workspace_invitation(&self, user_id: Uuid, workspace_name: &str, workspace_id: Uuid, invited_by: &str) -> Result<NotificationResponse, ApiError> {
+        let notification = CreateNotification {
+            user_id,
+            notification_type: "workspace_invitation".to_string(),
+            title: "Workspace Invitation".to_string(),
+            message: format!("{} invited you to join '{}'", invited_by, workspace_name),
+            metadata: Some(serde_json::json!({
+                "workspace_id": workspace_id
+            })),
+        };
+
+        self.create_notification(notification).await
+    }
+
     #[allow(dead_code)]
     pub async fn notify_task_overdue(&self, user_id: Uuid, task_title: &str, task_id: i32) -> Result<NotificationResponse, ApiError> {
         let notification = CreateNotification {
@@ -220,45 +372,183 @@ impl NotificationService {
     }
 }
 
+// How long a socket may stay open without presenting a valid token
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+// How often long-lived connections re-check their token expiry
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Tells a socket that sessions of its user were revoked
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct SessionsRevoked(pub Vec<Uuid>);
+
 // WebSocket Actor
 pub struct NotificationWebSocket {
     user_id: Option<Uuid>,
+    // Session of the token the socket last authenticated with
+    session_id: Option<Uuid>,
+    // Whether that session was found active; nothing is sent before
+    session_verified: bool,
+    token_expires_at: Option<i64>,
     notification_service: NotificationService,
     broadcast_rx: Option<broadcast::Receiver<(Uuid, NotificationResponse)>>,
 }
 
 impl NotificationWebSocket {
-    pub fn new(notification_service: NotificationService) -> Self {
+    /// Creates the actor, optionally pre-authenticated with claims verified
+    /// during the HTTP handshake. Without claims the client must send an
+    /// `auth` frame before `AUTH_TIMEOUT` elapses.
+    pub fn new(notification_service: NotificationService, claims: Option<Claims>) -> Self {
         let broadcast_rx = notification_service.get_broadcast_receiver();
-        
+        let user_id = claims
+            .as_ref()
+            .and_then(|claims| Uuid::parse_str(&claims.user_id).ok());
+        let session_id = claims
+            .as_ref()
+            .and_then(|claims| Uuid::parse_str(&claims.sid).ok());
+
         Self {
-            user_id: None,
+            user_id,
+            session_id,
+            session_verified: false,
+            token_expires_at: claims.map(|claims| claims.exp),
             notification_service,
             broadcast_rx: Some(broadcast_rx),
         }
     }
 
     fn start_broadcast_listener(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
-        if let Some(mut rx) = self.broadcast_rx.take() {
-            let user_id = self.user_id;
-            
-            ctx.spawn(
-                async move {
-                    while let Ok((target_user_id, notification)) = rx.recv().await {
-                        if Some(target_user_id) == user_id {
-                            return Some(WebSocketMessage::Notification(notification));
+        if let Some(rx) = self.broadcast_rx.take() {
+            let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+                loop {
+                    match rx.recv().await {
+                        Ok(item) => return Some((item, rx)),
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!("WebSocket listener lagged, skipped {} notifications", skipped);
                         }
+                        Err(broadcast::error::RecvError::Closed) => return None,
                     }
-                    None
                 }
-                .into_actor(self)
-                .map(|msg, _actor, ctx| {
-                    if let Some(message) = msg {
-                        let json = serde_json::to_string(&message).unwrap();
-                        ctx.text(json);
-                    }
-                }),
-            );
+            });
+
+            ctx.add_stream(stream);
+        }
+    }
+
+    fn send_message(ctx: &mut ws::WebsocketContext<Self>, message: &WebSocketMessage) {
+        if let Ok(json) = serde_json::to_string(message) {
+            ctx.text(json);
+        }
+    }
+
+    /// Sends a typed error frame and closes the socket with a policy violation.
+    fn close_with_error(&mut self, ctx: &mut ws::WebsocketContext<Self>, code: &str, message: &str) {
+        Self::send_message(ctx, &WebSocketMessage::Error {
+            code: code.to_string(),
+            message: message.to_string(),
+        });
+        ctx.close(Some(ws::CloseReason {
+            code: ws::CloseCode::Policy,
+            description: Some(code.to_string()),
+        }));
+        ctx.stop();
+    }
+
+    fn authenticate(&mut self, token: &str, ctx: &mut ws::WebsocketContext<Self>) {
+        let claims = match verify_token(token) {
+            Ok(claims) => claims,
+            Err(_) => {
+                self.close_with_error(ctx, "INVALID_TOKEN", "Invalid or expired token");
+                return;
+            }
+        };
+
+        let (user_id, session_id) = match (Uuid::parse_str(&claims.user_id), Uuid::parse_str(&claims.sid)) {
+            (Ok(user_id), Ok(session_id)) => (user_id, session_id),
+            _ => {
+                self.close_with_error(ctx, "INVALID_TOKEN", "Invalid user or session ID in token");
+                return;
+            }
+        };
+
+        // A connection may refresh its token, but never switch users
+        if let Some(current_user_id) = self.user_id {
+            if current_user_id != user_id {
+                self.close_with_error(ctx, "USER_MISMATCH", "Token belongs to a different user");
+                return;
+            }
+            self.session_id = Some(session_id);
+            self.token_expires_at = Some(claims.exp);
+            self.check_session(ctx);
+            return;
+        }
+
+        self.user_id = Some(user_id);
+        self.session_id = Some(session_id);
+        self.token_expires_at = Some(claims.exp);
+        self.register_connection(ctx);
+    }
+
+    /// Tokens outlive the revocation of their session, so the session is
+    /// looked up too: the socket is acknowledged once it is found active,
+    /// and closed otherwise.
+    fn check_session(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        let (Some(user_id), Some(session_id)) = (self.user_id, self.session_id) else {
+            return;
+        };
+        let pool = self.notification_service.pool.clone();
+
+        let is_active = async move { sessions::is_active(&pool, user_id, session_id).await };
+        ctx.spawn(is_active.into_actor(self).map(move |active, act, ctx| {
+            // A newer token may have replaced the session meanwhile
+            if act.session_id != Some(session_id) {
+                return;
+            }
+            match active {
+                Ok(true) => {
+                    act.session_verified = true;
+                    act.send_authenticated(ctx);
+                }
+                Ok(false) => act.close_with_error(ctx, "SESSION_REVOKED", "Session has been revoked"),
+                Err(e) => {
+                    tracing::error!("Failed to check WebSocket session: {}", e);
+                    act.close_with_error(ctx, "SESSION_CHECK_FAILED", "Could not verify the session");
+                }
+            }
+        }));
+    }
+
+    fn register_connection(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        if let Some(user_id) = self.user_id {
+            let service = self.notification_service.clone();
+            let addr = ctx.address();
+
+            tokio::spawn(async move {
+                service.add_connection(user_id, addr).await;
+            });
+
+            self.check_session(ctx);
+        }
+    }
+
+    fn send_authenticated(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        if let (Some(user_id), Some(expires_at)) = (self.user_id, self.token_expires_at) {
+            Self::send_message(ctx, &WebSocketMessage::Authenticated {
+                status: "success".to_string(),
+                user_id,
+                expires_at,
+            });
+        }
+    }
+
+    /// Closes the socket once its token has lapsed. Returns whether it is still valid.
+    fn check_token_expiry(&mut self, ctx: &mut ws::WebsocketContext<Self>) -> bool {
+        match self.token_expires_at {
+            Some(expires_at) if expires_at <= Utc::now().timestamp() => {
+                self.close_with_error(ctx, "TOKEN_EXPIRED", "Token has expired");
+                false
+            }
+            _ => true,
         }
     }
 }
@@ -269,6 +559,20 @@ impl Actor for NotificationWebSocket {
     fn started(&mut self, ctx: &mut Self::Context) {
         tracing::info!("WebSocket connection started");
         self.start_broadcast_listener(ctx);
+
+        if self.user_id.is_some() {
+            self.register_connection(ctx);
+        } else {
+            ctx.run_later(AUTH_TIMEOUT, |act, ctx| {
+                if act.user_id.is_none() {
+                    act.close_with_error(ctx, "AUTH_TIMEOUT", "Authentication timed out");
+                }
+            });
+        }
+
+        ctx.run_interval(EXPIRY_CHECK_INTERVAL, |act, ctx| {
+            act.check_token_expiry(ctx);
+        });
     }
 
     fn stopped(&mut self, ctx: &mut Self::Context) {
@@ -284,26 +588,51 @@ impl Actor for NotificationWebSocket {
     }
 }
 
+impl Handler<SessionsRevoked> for NotificationWebSocket {
+    type Result = ();
+
+    fn handle(&mut self, SessionsRevoked(session_ids): SessionsRevoked, ctx: &mut Self::Context) {
+        if self.session_id.is_some_and(|session_id| session_ids.contains(&session_id)) {
+            self.close_with_error(ctx, "SESSION_REVOKED", "Session has been revoked");
+        }
+    }
+}
+
+impl StreamHandler<(Uuid, NotificationResponse)> for NotificationWebSocket {
+    fn handle(&mut self, (target_user_id, notification): (Uuid, NotificationResponse), ctx: &mut Self::Context) {
+        if self.user_id != Some(target_user_id) || !self.session_verified {
+            return;
+        }
+
+        // Never deliver on a connection whose token has lapsed
+        if self.check_token_expiry(ctx) {
+            Self::send_message(ctx, &WebSocketMessage::Notification(notification));
+        }
+    }
+
+    fn finished(&mut self, _ctx: &mut Self::Context) {
+        // The broadcast channel only closes on shutdown; keep the socket alive until then
+    }
+}
+
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for NotificationWebSocket {
     fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
         match msg {
             Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
             Ok(ws::Message::Pong(_)) => {},
             Ok(ws::Message::Text(text)) => {
-                // Handle authentication message
-                if let Ok(auth_msg) = serde_json::from_str::<serde_json::Value>(&text) {
-                    if let Some(user_id_str) = auth_msg.get("user_id").and_then(|v| v.as_str()) {
-                        if let Ok(user_id) = Uuid::parse_str(user_id_str) {
-                            self.user_id = Some(user_id);
-                            let service = self.notification_service.clone();
-                            let addr = ctx.address();
-                            
-                            tokio::spawn(async move {
-                                service.add_connection(user_id, addr).await;
-                            });
-                            
-                            ctx.text(r#"{"type":"authenticated","status":"success"}"#);
-                        }
+                match serde_json::from_str::<WebSocketMessage>(&text) {
+                    Ok(WebSocketMessage::Auth { token }) => self.authenticate(&token, ctx),
+                    _ if self.user_id.is_none() => {
+                        self.close_with_error(ctx, "AUTH_REQUIRED", "First message must be an auth message");
+                    }
+                    Ok(WebSocketMessage::Ping) => Self::send_message(ctx, &WebSocketMessage::Pong),
+                    Ok(_) => {},
+                    Err(_) => {
+                        Self::send_message(ctx, &WebSocketMessage::Error {
+                            code: "INVALID_MESSAGE".to_string(),
+                            message: "Unrecognized message".to_string(),
+                        });
                     }
                 }
             },
@@ -0,0 +1,59 @@
+use chrono::{Duration, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::utils::tokens::{generate_token, hash_token};
+
+// How long a reset link works
+pub const TOKEN_TTL_MINUTES: i64 = 60;
+// Requests for the same account closer together than this send nothing
+pub const REQUEST_INTERVAL_SECONDS: f64 = 60.0;
+
+/// Creates a reset token for `user_id` and returns it, or None when one was
+/// requested moments ago
+pub async fn issue(pool: &PgPool, user_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+    let token = generate_token();
+
+    let issued = sqlx::query(
+        r#"
+        INSERT INTO password_resets (user_id, token_hash, expires_at)
+        SELECT $1, $2, $3
+        WHERE NOT EXISTS (
+            SELECT 1 FROM password_resets
+            WHERE user_id = $1 AND created_at > NOW() - make_interval(secs => $4)
+        )
+        "#
+    )
+    .bind(user_id)
+    .bind(hash_token(&token))
+    .bind(Utc::now() + Duration::minutes(TOKEN_TTL_MINUTES))
+    .bind(REQUEST_INTERVAL_SECONDS)
+    .execute(pool)
+    .await?;
+
+    Ok((issued.rows_affected() > 0).then_some(token))
+}
+
+/// Uses up `token` and returns whose it was; None if it is unknown, expired or
+/// already used. Every other outstanding token of that user stops working too.
+pub async fn consume(conn: &mut PgConnection, token: &str) -> Result<Option<Uuid>, sqlx::Error> {
+    let user_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        UPDATE password_resets SET used_at = NOW()
+        WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+        RETURNING user_id
+        "#
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(user_id) = user_id {
+        sqlx::query("UPDATE password_resets SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL")
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(user_id)
+}
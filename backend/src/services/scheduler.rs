@@ -1,14 +1,28 @@
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::interval;
 use sqlx::{PgPool, Row};
 use chrono::{Utc, DateTime};
 use uuid::Uuid;
 
+use crate::services::accounts;
+use crate::services::archive;
+use crate::services::attachments;
 use crate::services::notification_service::NotificationService;
+use crate::services::sessions;
+use crate::services::storage::StorageBackend;
+use crate::services::task_recurrence;
+use crate::services::trash;
 
 pub struct TaskScheduler {
     pool: PgPool,
     notification_service: NotificationService,
+    // Attachment blobs are purged only when a storage backend is configured
+    storage: Option<Arc<dyn StorageBackend>>,
+    // How long deleted tasks stay in the trash before they are purged
+    trash_retention: chrono::Duration,
+    // How long deleted accounts can be recovered before they are purged
+    account_deletion_grace: chrono::Duration,
 }
 
 impl TaskScheduler {
@@ -16,12 +30,33 @@ impl TaskScheduler {
         Self {
             pool,
             notification_service,
+            storage: None,
+            trash_retention: chrono::Duration::days(trash::DEFAULT_RETENTION_DAYS),
+            account_deletion_grace: chrono::Duration::days(accounts::DEFAULT_DELETION_GRACE_DAYS),
         }
     }
 
+    pub fn with_storage(mut self, storage: Arc<dyn StorageBackend>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    pub fn with_trash_retention(mut self, retention: chrono::Duration) -> Self {
+        self.trash_retention = retention;
+        self
+    }
+
+    pub fn with_account_deletion_grace(mut self, grace: chrono::Duration) -> Self {
+        self.account_deletion_grace = grace;
+        self
+    }
+
     pub fn start(&self) {
         let pool = self.pool.clone();
         let notification_service = self.notification_service.clone();
+        let storage = self.storage.clone();
+        let trash_retention = self.trash_retention;
+        let account_deletion_grace = self.account_deletion_grace;
 
         // Execute every hour
         tokio::spawn(async move {
@@ -37,6 +72,34 @@ impl TaskScheduler {
                 if let Err(e) = Self::check_overdue_tasks(&pool, &notification_service).await {
                     tracing::error!("Error checking overdue tasks: {}", e);
                 }
+
+                if let Err(e) = Self::generate_recurring_tasks(&pool).await {
+                    tracing::error!("Error generating recurring tasks: {}", e);
+                }
+
+                if let Err(e) = Self::archive_finished_tasks(&pool).await {
+                    tracing::error!("Error archiving finished tasks: {}", e);
+                }
+
+                // Before the blob purge, so purged tasks' attachments go in the same run
+                if let Err(e) = Self::purge_trash(&pool, trash_retention).await {
+                    tracing::error!("Error purging trashed tasks: {}", e);
+                }
+
+                // Also before the blob purge, for the attachments of deleted accounts
+                if let Err(e) = Self::purge_deleted_accounts(&pool, account_deletion_grace).await {
+                    tracing::error!("Error purging deleted accounts: {}", e);
+                }
+
+                if let Some(storage) = &storage {
+                    if let Err(e) = attachments::purge_deleted_blobs(&pool, storage.as_ref()).await {
+                        tracing::error!("Error purging attachment blobs: {}", e);
+                    }
+                }
+
+                if let Err(e) = Self::purge_expired_sessions(&pool).await {
+                    tracing::error!("Error purging expired sessions: {}", e);
+                }
             }
         });
     }
@@ -55,6 +118,8 @@ impl TaskScheduler {
             FROM tasks 
             WHERE due_date BETWEEN $1 AND $2 
                 AND status != 'done'
+                AND deleted_at IS NULL
+                AND archived_at IS NULL
             "#,
         )
         .bind(now)
@@ -91,6 +156,8 @@ impl TaskScheduler {
             FROM tasks 
             WHERE due_date < $1 
                 AND status != 'done'
+                AND deleted_at IS NULL
+                AND archived_at IS NULL
             "#,
         )
         .bind(now)
@@ -110,11 +177,65 @@ impl TaskScheduler {
         Ok(())
     }
 
+    async fn generate_recurring_tasks(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+        // Series that lapsed without a completion still move on to their next occurrence
+        let generated = task_recurrence::generate_lapsed_instances(pool).await?;
+
+        if generated > 0 {
+            tracing::info!("Generated {} recurring task instances", generated);
+        }
+        Ok(())
+    }
+
+    async fn archive_finished_tasks(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+        let archived = archive::archive_finished(pool).await?;
+
+        if archived > 0 {
+            tracing::info!("Archived {} finished tasks", archived);
+        }
+        Ok(())
+    }
+
+    async fn purge_trash(pool: &PgPool, retention: chrono::Duration) -> Result<(), Box<dyn std::error::Error>> {
+        let purged = trash::purge_expired(pool, retention).await?;
+
+        if purged > 0 {
+            tracing::info!("Purged {} tasks from the trash", purged);
+        }
+        Ok(())
+    }
+
+    async fn purge_deleted_accounts(pool: &PgPool, grace: chrono::Duration) -> Result<(), Box<dyn std::error::Error>> {
+        let purged = accounts::purge_deleted(pool, grace).await?;
+
+        if purged > 0 {
+            tracing::info!("Purged {} deleted accounts", purged);
+        }
+        Ok(())
+    }
+
+    async fn purge_expired_sessions(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+        let purged = sessions::purge_expired(pool).await?;
+
+        if purged > 0 {
+            tracing::info!("Purged {} expired sessions", purged);
+        }
+        Ok(())
+    }
+
     // Test method to manually execute checks
     #[allow(dead_code)]
     pub async fn run_checks_now(&self) -> Result<(), Box<dyn std::error::Error>> {
         Self::check_due_soon_tasks(&self.pool, &self.notification_service).await?;
         Self::check_overdue_tasks(&self.pool, &self.notification_service).await?;
+        Self::generate_recurring_tasks(&self.pool).await?;
+        Self::archive_finished_tasks(&self.pool).await?;
+        Self::purge_trash(&self.pool, self.trash_retention).await?;
+        Self::purge_deleted_accounts(&self.pool, self.account_deletion_grace).await?;
+        if let Some(storage) = &self.storage {
+            attachments::purge_deleted_blobs(&self.pool, storage.as_ref()).await?;
+        }
+        Self::purge_expired_sessions(&self.pool).await?;
         Ok(())
     }
 }
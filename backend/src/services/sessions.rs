@@ -0,0 +1,228 @@
+use actix_web::http::header;
+use actix_web::HttpRequest;
+use chrono::{Duration, Utc};
+use sqlx::{PgExecutor, PgPool};
+use std::env;
+use uuid::Uuid;
+
+use crate::models::Session;
+use crate::utils::tokens::{generate_token, hash_token};
+
+pub const DEFAULT_REFRESH_TOKEN_DAYS: i64 = 30;
+
+/// Requests record when their session was last seen at most this often
+pub const LAST_SEEN_INTERVAL_SECONDS: f64 = 300.0;
+
+/// How long a session lasts without being refreshed, from REFRESH_TOKEN_DAYS;
+/// 30 days for unset or invalid values
+pub fn refresh_ttl_from_env() -> Duration {
+    let days = env::var("REFRESH_TOKEN_DAYS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|&days| days > 0)
+        .unwrap_or(DEFAULT_REFRESH_TOKEN_DAYS);
+
+    Duration::days(days)
+}
+
+/// The device a session is used from, as far as the request tells. Only
+/// shown back to the user, so the forwarded address is good enough.
+pub struct SessionClient {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl SessionClient {
+    pub fn from_request(req: &HttpRequest) -> Self {
+        Self {
+            user_agent: req
+                .headers()
+                .get(header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            ip_address: req.connection_info().realip_remote_addr().map(str::to_string),
+        }
+    }
+}
+
+pub enum RefreshError {
+    // Unknown, malformed, expired or already revoked
+    Invalid,
+    // A token the session has already rotated away from; the session is now revoked
+    Reused { user_id: Uuid, session_id: Uuid },
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for RefreshError {
+    fn from(error: sqlx::Error) -> Self {
+        RefreshError::Database(error)
+    }
+}
+
+pub struct RefreshedSession {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub refresh_token: String,
+}
+
+// Refresh tokens name their session, so a replayed old token can be traced
+// back to the family it belongs to
+fn new_refresh_token(session_id: Uuid) -> String {
+    format!("{}.{}", session_id, generate_token())
+}
+
+/// Starts a session for `user_id`; returns its id and first refresh token
+pub async fn create<'e>(executor: impl PgExecutor<'e>, user_id: Uuid, client: &SessionClient) -> Result<(Uuid, String), sqlx::Error> {
+    let session_id = Uuid::new_v4();
+    let refresh_token = new_refresh_token(session_id);
+
+    sqlx::query(
+        r#"
+        INSERT INTO sessions (id, user_id, refresh_token_hash, expires_at, user_agent, ip_address)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .bind(hash_token(&refresh_token))
+    .bind(Utc::now() + refresh_ttl_from_env())
+    .bind(&client.user_agent)
+    .bind(&client.ip_address)
+    .execute(executor)
+    .await?;
+
+    Ok((session_id, refresh_token))
+}
+
+/// Trades a refresh token for a new one, extending its session. Presenting a
+/// token the session has already been refreshed past means it leaked, so the
+/// session is revoked along with every token it issued.
+pub async fn refresh(pool: &PgPool, refresh_token: &str, client: &SessionClient) -> Result<RefreshedSession, RefreshError> {
+    let session_id = refresh_token
+        .trim()
+        .split_once('.')
+        .and_then(|(session_id, _)| Uuid::parse_str(session_id).ok())
+        .ok_or(RefreshError::Invalid)?;
+    let presented_hash = hash_token(refresh_token);
+    let next_token = new_refresh_token(session_id);
+
+    // The presented token is remembered, so a later replay of it is recognised
+    let user_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        WITH rotated AS (
+            UPDATE sessions
+            SET refresh_token_hash = $3, refreshed_at = NOW(), last_seen_at = NOW(), expires_at = $4,
+                user_agent = COALESCE($5, user_agent), ip_address = COALESCE($6, ip_address)
+            WHERE id = $1 AND refresh_token_hash = $2 AND revoked_at IS NULL AND expires_at > NOW()
+            RETURNING id, user_id
+        ), remembered AS (
+            INSERT INTO session_rotated_tokens (token_hash, session_id)
+            SELECT $2, id FROM rotated
+        )
+        SELECT user_id FROM rotated
+        "#
+    )
+    .bind(session_id)
+    .bind(&presented_hash)
+    .bind(hash_token(&next_token))
+    .bind(Utc::now() + refresh_ttl_from_env())
+    .bind(&client.user_agent)
+    .bind(&client.ip_address)
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(user_id) = user_id {
+        return Ok(RefreshedSession { session_id, user_id, refresh_token: next_token });
+    }
+
+    // Only a token the session really handed out and rotated away from counts
+    // as a replay; anything else that merely names the session is just invalid
+    let revoked = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        UPDATE sessions SET revoked_at = NOW()
+        WHERE id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            AND EXISTS (SELECT 1 FROM session_rotated_tokens WHERE session_id = $1 AND token_hash = $2)
+        RETURNING user_id
+        "#
+    )
+    .bind(session_id)
+    .bind(&presented_hash)
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(user_id) = revoked {
+        tracing::warn!("Refresh token reuse detected, revoked session {}", session_id);
+        return Err(RefreshError::Reused { user_id, session_id });
+    }
+
+    Err(RefreshError::Invalid)
+}
+
+/// `user_id`'s sessions still in use, most recently seen first; `current`
+/// marks `current_session_id`
+pub async fn list(pool: &PgPool, user_id: Uuid, current_session_id: Uuid) -> Result<Vec<Session>, sqlx::Error> {
+    sqlx::query_as::<_, Session>(
+        r#"
+        SELECT id, user_agent, ip_address, created_at, last_seen_at, expires_at, id = $2 AS current
+        FROM sessions
+        WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+        ORDER BY last_seen_at DESC, id
+        "#
+    )
+    .bind(user_id)
+    .bind(current_session_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Whether `session_id` is one of `user_id`'s sessions and hasn't been revoked
+pub async fn is_active(pool: &PgPool, user_id: Uuid, session_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM sessions WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL)"
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Revokes one of `user_id`'s sessions; false if it wasn't active
+pub async fn revoke(pool: &PgPool, user_id: Uuid, session_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("UPDATE sessions SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL")
+        .bind(session_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Revokes every active session of `user_id`; returns their ids
+pub async fn revoke_all(pool: &PgPool, user_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>("UPDATE sessions SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL RETURNING id")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Revokes every active session of `user_id` except `keep_session_id`;
+/// returns their ids
+pub async fn revoke_others(pool: &PgPool, user_id: Uuid, keep_session_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>(
+        "UPDATE sessions SET revoked_at = NOW() WHERE user_id = $1 AND id <> $2 AND revoked_at IS NULL RETURNING id"
+    )
+    .bind(user_id)
+    .bind(keep_session_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Deletes sessions that have expired; their tokens can no longer be
+/// refreshed or replayed. Returns how many were deleted.
+pub async fn purge_expired(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM sessions WHERE expires_at < NOW()")
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::stream::BoxStream;
+use std::env;
+use std::sync::Arc;
+
+use crate::utils::byte_range::ByteRange;
+
+pub mod local;
+pub mod s3;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+/// Chunks of a stored blob, as read from the backend
+pub type BlobStream = BoxStream<'static, Result<Bytes, std::io::Error>>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("blob not found")]
+    NotFound,
+    #[error("storage I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("storage backend error: {0}")]
+    Backend(String),
+}
+
+/// Where attachment contents live. Keys are generated by the application and
+/// only contain `[a-z0-9-/]`.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> Result<(), StorageError>;
+
+    /// Streams the blob, or only `range` of it
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> Result<BlobStream, StorageError>;
+
+    /// Deleting a missing blob is not an error
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+}
+
+/// Builds the backend selected by STORAGE_BACKEND (local|s3), local if unset
+pub fn storage_from_env() -> Result<Arc<dyn StorageBackend>, String> {
+    let backend = env::var("STORAGE_BACKEND").unwrap_or_else(|_| "local".to_string());
+
+    match backend.to_lowercase().as_str() {
+        "local" => {
+            let root = env::var("STORAGE_LOCAL_PATH").unwrap_or_else(|_| "./data/attachments".to_string());
+            Ok(Arc::new(LocalStorage::new(root)))
+        }
+        "s3" => {
+            let var = |name: &str| env::var(name).map_err(|_| format!("{} must be set for the s3 storage backend", name));
+            Ok(Arc::new(S3Storage::new(
+                var("S3_ENDPOINT")?,
+                var("S3_BUCKET")?,
+                env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                var("S3_ACCESS_KEY_ID")?,
+                var("S3_SECRET_ACCESS_KEY")?,
+            )))
+        }
+        other => Err(format!("Unknown STORAGE_BACKEND '{}', expected local or s3", other)),
+    }
+}
@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::Utc;
+use futures_util::StreamExt;
+use reqwest::{header, Client, Method, StatusCode, Url};
+
+use super::{BlobStream, StorageBackend, StorageError};
+use crate::utils::byte_range::ByteRange;
+use crate::utils::sigv4::{self, CanonicalRequest, SigningKey, EMPTY_PAYLOAD_SHA256};
+
+/// Stores blobs in a bucket of an S3-compatible service (AWS S3, MinIO, ...),
+/// addressed path-style as `{endpoint}/{bucket}/{key}`
+pub struct S3Storage {
+    client: Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3Storage {
+    pub fn new(
+        endpoint: impl Into<String>,
+        bucket: impl Into<String>,
+        region: impl Into<String>,
+        access_key: impl Into<String>,
+        secret_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: Client::new(),
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            bucket: bucket.into(),
+            region: region.into(),
+            access_key: access_key.into(),
+            secret_key: secret_key.into(),
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        body: Option<(Bytes, &str)>,
+        range: Option<ByteRange>,
+    ) -> Result<reqwest::Response, StorageError> {
+        let url = format!(
+            "{}/{}/{}",
+            self.endpoint,
+            sigv4::uri_encode(&self.bucket, true),
+            sigv4::uri_encode(key, false)
+        );
+        let url = Url::parse(&url).map_err(|e| StorageError::Backend(format!("invalid S3 endpoint: {}", e)))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(StorageError::Backend("S3 endpoint has no host".to_string())),
+        };
+
+        let payload_sha256 = match &body {
+            Some((data, _)) => sigv4::sha256_hex(data),
+            None => EMPTY_PAYLOAD_SHA256.to_string(),
+        };
+        let now = Utc::now();
+        let amz_date = sigv4::amz_date(now);
+
+        let authorization = sigv4::authorization(
+            &SigningKey {
+                access_key: &self.access_key,
+                secret_key: &self.secret_key,
+                region: &self.region,
+                service: "s3",
+            },
+            &CanonicalRequest {
+                method: method.as_str(),
+                path: url.path(),
+                query: "",
+                headers: &[
+                    ("host", &host),
+                    ("x-amz-content-sha256", &payload_sha256),
+                    ("x-amz-date", &amz_date),
+                ],
+                payload_sha256: &payload_sha256,
+            },
+            now,
+        );
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("x-amz-content-sha256", &payload_sha256)
+            .header("x-amz-date", &amz_date)
+            .header(header::AUTHORIZATION, authorization);
+
+        if let Some(range) = range {
+            request = request.header(header::RANGE, format!("bytes={}-{}", range.start, range.end));
+        }
+        if let Some((data, content_type)) = body {
+            request = request.header(header::CONTENT_TYPE, content_type).body(data);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Err(StorageError::NotFound),
+            status if status.is_success() => Ok(response),
+            status => {
+                let message = response.text().await.unwrap_or_default();
+                Err(StorageError::Backend(format!("S3 responded {}: {}", status, message)))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StorageBackend for S3Storage {
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> Result<(), StorageError> {
+        self.send(Method::PUT, key, Some((data, content_type)), None).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> Result<BlobStream, StorageError> {
+        let response = self.send(Method::GET, key, None, range).await?;
+        Ok(response.bytes_stream().map(|chunk| chunk.map_err(std::io::Error::other)).boxed())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match self.send(Method::DELETE, key, None, None).await {
+            Err(StorageError::NotFound) => Ok(()),
+            result => result.map(|_| ()),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use sqlx::{PgExecutor, PgPool, Row};
+use uuid::Uuid;
+
+/// Whether making `task_id` blocked by `blocked_by_id` would close a loop,
+/// i.e. `blocked_by_id` already (transitively) waits on `task_id`.
+pub async fn would_create_cycle(pool: &PgPool, task_id: Uuid, blocked_by_id: Uuid) -> Result<bool, sqlx::Error> {
+    if task_id == blocked_by_id {
+        return Ok(true);
+    }
+
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        WITH RECURSIVE blockers AS (
+            SELECT blocked_by_id FROM task_dependencies WHERE task_id = $1
+            UNION
+            SELECT d.blocked_by_id
+            FROM task_dependencies d
+            JOIN blockers b ON d.task_id = b.blocked_by_id
+        )
+        SELECT EXISTS(SELECT 1 FROM blockers WHERE blocked_by_id = $2)
+        "#,
+    )
+    .bind(blocked_by_id)
+    .bind(task_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Blockers of `task_id` that are not done or trashed, as (id, title) pairs.
+pub async fn open_blockers<'e>(executor: impl PgExecutor<'e>, task_id: Uuid) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT b.id, b.title
+        FROM task_dependencies d
+        JOIN tasks b ON b.id = d.blocked_by_id
+        WHERE d.task_id = $1 AND b.status != 'done' AND b.deleted_at IS NULL
+        ORDER BY b.created_at
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.get("id"), row.get("title"))).collect())
+}
+
+/// A task whose last open blocker was just finished.
+pub struct UnblockedTask {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+}
+
+/// Tasks blocked by `blocker_id` that have no other open blockers left.
+pub async fn unblocked_by(pool: &PgPool, blocker_id: Uuid) -> Result<Vec<UnblockedTask>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT t.id, t.user_id, t.title
+        FROM task_dependencies d
+        JOIN tasks t ON t.id = d.task_id
+        WHERE d.blocked_by_id = $1
+            AND t.status != 'done'
+            AND t.deleted_at IS NULL
+            AND NOT EXISTS (
+                SELECT 1
+                FROM task_dependencies o
+                JOIN tasks b ON b.id = o.blocked_by_id
+                WHERE o.task_id = t.id AND b.status != 'done' AND b.deleted_at IS NULL
+            )
+        "#,
+    )
+    .bind(blocker_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| UnblockedTask {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            title: row.get("title"),
+        })
+        .collect())
+}
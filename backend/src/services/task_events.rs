@@ -0,0 +1,94 @@
+use serde_json::{json, Map, Value};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::models::{Task, TaskEventType};
+
+// Fields whose changes are logged, as named in the task JSON
+const TRACKED_FIELDS: &[&str] = &[
+    "title",
+    "description",
+    "status",
+    "priority",
+    "due_date",
+    "tags",
+    "parent_id",
+    "project_id",
+    "assignee_id",
+    "recurrence_rule",
+];
+
+fn tracked_values(task: &Task) -> Map<String, Value> {
+    let Ok(Value::Object(mut fields)) = serde_json::to_value(task) else {
+        return Map::new();
+    };
+    fields.retain(|field, _| TRACKED_FIELDS.contains(&field.as_str()));
+    fields
+}
+
+/// `{"field": {"old": ..., "new": ...}}` for each tracked field that differs
+/// between two versions of a task. Either side may be missing, for tasks
+/// being created or deleted.
+pub fn diff(before: Option<&Task>, after: Option<&Task>) -> Map<String, Value> {
+    let before = before.map(tracked_values).unwrap_or_default();
+    let after = after.map(tracked_values).unwrap_or_default();
+
+    TRACKED_FIELDS
+        .iter()
+        .filter_map(|&field| {
+            let old = before.get(field).cloned().unwrap_or(Value::Null);
+            let new = after.get(field).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| (field.to_string(), json!({ "old": old, "new": new })))
+        })
+        .collect()
+}
+
+/// Appends an event for a task, with its title as of after the change.
+/// Changes made by the system, like purges, have no actor.
+pub async fn record<'e>(
+    executor: impl PgExecutor<'e>,
+    task_id: Uuid,
+    workspace_id: Uuid,
+    task_title: &str,
+    actor_id: Option<Uuid>,
+    event_type: TaskEventType,
+    changes: Map<String, Value>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO task_events (task_id, workspace_id, actor_id, event_type, task_title, changes)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#
+    )
+    .bind(task_id)
+    .bind(workspace_id)
+    .bind(actor_id)
+    .bind(event_type)
+    .bind(task_title)
+    .bind(Value::Object(changes))
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Records the change from `before` to `after`, classed as a status change
+/// when the status moved. Nothing is recorded when no tracked field changed.
+pub async fn record_update<'e>(
+    executor: impl PgExecutor<'e>,
+    before: &Task,
+    after: &Task,
+    actor_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    let changes = diff(Some(before), Some(after));
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let event_type = if changes.contains_key("status") {
+        TaskEventType::StatusChanged
+    } else {
+        TaskEventType::Updated
+    };
+    record(executor, after.id, after.workspace_id, &after.title, Some(actor_id), event_type, changes).await
+}
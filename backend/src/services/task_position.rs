@@ -0,0 +1,140 @@
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::models::TaskStatus;
+
+// Tasks sharing a status column with the task being moved, leaving it out
+const COLUMN: &str = "workspace_id = $1 AND status = $2 AND deleted_at IS NULL AND id <> $3";
+
+/// Position for `task_id` in the `status` column of its workspace, right
+/// after `previous_id` and right before `next_id`. With one neighbour the
+/// other is read from the column; with neither the task goes last. Only when
+/// repeated moves have used up the room between two neighbours is the column
+/// spread out again. None if `previous_id` doesn't come before `next_id`.
+pub async fn position_between(
+    conn: &mut PgConnection,
+    workspace_id: Uuid,
+    status: &TaskStatus,
+    task_id: Uuid,
+    previous_id: Option<Uuid>,
+    next_id: Option<Uuid>,
+) -> Result<Option<f64>, sqlx::Error> {
+    for attempt in 0..2 {
+        let previous = match previous_id {
+            Some(id) => Some(position_of(conn, id).await?),
+            None => None,
+        };
+        let next = match next_id {
+            Some(id) => Some(position_of(conn, id).await?),
+            None => None,
+        };
+
+        let (previous, next) = match (previous, next) {
+            (Some(previous), None) => (Some(previous), column_bound(conn, workspace_id, status, task_id, "MIN", ">", previous).await?),
+            (None, Some(next)) => (column_bound(conn, workspace_id, status, task_id, "MAX", "<", next).await?, Some(next)),
+            (None, None) => (column_last(conn, workspace_id, status, task_id).await?, None),
+            both => both,
+        };
+
+        match (previous, next) {
+            (Some(previous), Some(next)) => {
+                if previous >= next {
+                    return Ok(None);
+                }
+                let middle = previous + (next - previous) / 2.0;
+                if previous < middle && middle < next {
+                    return Ok(Some(middle));
+                }
+                if attempt == 0 {
+                    spread_column(conn, workspace_id, status, task_id).await?;
+                }
+            }
+            (Some(previous), None) => return Ok(Some(previous + 1.0)),
+            (None, Some(next)) => return Ok(Some(next - 1.0)),
+            (None, None) => return Ok(Some(chrono::Utc::now().timestamp_micros() as f64 / 1_000_000.0)),
+        }
+    }
+
+    Ok(None)
+}
+
+/// Position at the end of the `status` column, for a task changing status
+pub async fn position_last(
+    conn: &mut PgConnection,
+    workspace_id: Uuid,
+    status: &TaskStatus,
+    task_id: Uuid,
+) -> Result<f64, sqlx::Error> {
+    let position = position_between(conn, workspace_id, status, task_id, None, None).await?;
+    Ok(position.expect("a task without neighbours always has a position"))
+}
+
+async fn position_of(conn: &mut PgConnection, task_id: Uuid) -> Result<f64, sqlx::Error> {
+    sqlx::query_scalar::<_, f64>("SELECT position FROM tasks WHERE id = $1")
+        .bind(task_id)
+        .fetch_one(conn)
+        .await
+}
+
+// Closest position in the column past `position`, in the direction of `comparison`
+async fn column_bound(
+    conn: &mut PgConnection,
+    workspace_id: Uuid,
+    status: &TaskStatus,
+    task_id: Uuid,
+    aggregate: &str,
+    comparison: &str,
+    position: f64,
+) -> Result<Option<f64>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<f64>>(&format!(
+        "SELECT {}(position) FROM tasks WHERE {} AND position {} $4",
+        aggregate, COLUMN, comparison
+    ))
+    .bind(workspace_id)
+    .bind(status)
+    .bind(task_id)
+    .bind(position)
+    .fetch_one(conn)
+    .await
+}
+
+async fn column_last(conn: &mut PgConnection, workspace_id: Uuid, status: &TaskStatus, task_id: Uuid) -> Result<Option<f64>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<f64>>(&format!("SELECT MAX(position) FROM tasks WHERE {}", COLUMN))
+        .bind(workspace_id)
+        .bind(status)
+        .bind(task_id)
+        .fetch_one(conn)
+        .await
+}
+
+// Spaces the column's tasks evenly over the range they already cover, in
+// their current order
+async fn spread_column(conn: &mut PgConnection, workspace_id: Uuid, status: &TaskStatus, task_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        r#"
+        WITH ranked AS (
+            SELECT id,
+                ROW_NUMBER() OVER (ORDER BY position, id) - 1 AS place,
+                COUNT(*) OVER () AS total,
+                MIN(position) OVER () AS lowest
+            FROM tasks
+            WHERE {}
+        ),
+        span AS (
+            SELECT GREATEST(MAX(position) - MIN(position), COUNT(*)) AS width FROM tasks WHERE {}
+        )
+        UPDATE tasks t
+        SET position = r.lowest + r.place * span.width / GREATEST(r.total - 1, 1)
+        FROM ranked r, span
+        WHERE t.id = r.id
+        "#,
+        COLUMN, COLUMN
+    ))
+    .bind(workspace_id)
+    .bind(status)
+    .bind(task_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
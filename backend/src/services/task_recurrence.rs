@@ -0,0 +1,94 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{Task, TaskEventType};
+use crate::services::task_events;
+use crate::utils::recurrence::RecurrenceRule;
+
+/// Creates the instance that follows `task` in its recurrence series, due at
+/// the first occurrence after now, and records its creation by `actor_id`
+/// (None when the scheduler does it). Returns the new task id, or `None` when
+/// the series has ended or that occurrence was already generated.
+pub async fn generate_next_instance(pool: &PgPool, task: &Task, actor_id: Option<Uuid>) -> Result<Option<Uuid>, sqlx::Error> {
+    let Some(rule) = task
+        .recurrence_rule
+        .as_deref()
+        .and_then(|rule| RecurrenceRule::parse(rule).ok())
+    else {
+        return Ok(None);
+    };
+
+    let now = Utc::now();
+    let current = task.due_date.unwrap_or(now);
+    let index = task.recurrence_index.unwrap_or(1);
+
+    let Some((due_date, next_index)) = rule.next_occurrence(current, index, now) else {
+        return Ok(None);
+    };
+
+    let mut tx = pool.begin().await?;
+
+    let instance = sqlx::query_as::<_, Task>(
+        r#"
+        INSERT INTO tasks (
+            id, user_id, title, description, status, priority, due_date, tags, parent_id,
+            project_id, workspace_id, recurrence_rule, recurrence_series_id, recurrence_index
+        )
+        SELECT $2, user_id, title, description, 'todo', priority, $3, tags, parent_id,
+            project_id, workspace_id, recurrence_rule, COALESCE(recurrence_series_id, id), $4
+        FROM tasks
+        WHERE id = $1
+        ON CONFLICT (recurrence_series_id, recurrence_index) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(task.id)
+    .bind(Uuid::new_v4())
+    .bind(due_date)
+    .bind(next_index)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(instance) = instance else {
+        return Ok(None);
+    };
+
+    let changes = task_events::diff(None, Some(&instance));
+    task_events::record(&mut *tx, instance.id, instance.workspace_id, &instance.title, actor_id, TaskEventType::Created, changes).await?;
+
+    tx.commit().await?;
+    Ok(Some(instance.id))
+}
+
+/// Generates the next instance of every series whose latest instance went
+/// past its due date without being completed. Trashing the latest instance
+/// ends the series. Returns how many were created.
+pub async fn generate_lapsed_instances(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let lapsed = sqlx::query_as::<_, Task>(
+        r#"
+        SELECT * FROM (
+            SELECT DISTINCT ON (recurrence_series_id) *
+            FROM tasks
+            WHERE recurrence_series_id IS NOT NULL
+            ORDER BY recurrence_series_id, recurrence_index DESC
+        ) latest
+        WHERE recurrence_rule IS NOT NULL
+            AND deleted_at IS NULL
+            AND status != 'done'
+            AND due_date < $1
+        "#,
+    )
+    .bind(Utc::now())
+    .fetch_all(pool)
+    .await?;
+
+    let mut generated = 0;
+    for task in &lapsed {
+        if generate_next_instance(pool, task, None).await?.is_some() {
+            generated += 1;
+        }
+    }
+
+    Ok(generated)
+}
@@ -0,0 +1,121 @@
+use sqlx::{PgConnection, PgPool, Row};
+use uuid::Uuid;
+
+use crate::models::TaskStatus;
+
+/// Maximum number of levels in a task hierarchy, counting the root task.
+pub const MAX_TASK_DEPTH: i32 = 5;
+
+/// Number of levels from the root down to `task_id` (a root task has depth 1).
+pub async fn task_depth(pool: &PgPool, task_id: Uuid) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar::<_, i32>(
+        r#"
+        WITH RECURSIVE ancestors AS (
+            SELECT id, parent_id, 1 AS depth FROM tasks WHERE id = $1
+            UNION ALL
+            SELECT t.id, t.parent_id, a.depth + 1
+            FROM tasks t
+            JOIN ancestors a ON t.id = a.parent_id
+            WHERE a.depth < $2
+        )
+        SELECT COALESCE(MAX(depth), 0) FROM ancestors
+        "#,
+    )
+    .bind(task_id)
+    .bind(MAX_TASK_DEPTH + 1)
+    .fetch_one(pool)
+    .await
+}
+
+/// Number of levels in the subtree rooted at `task_id` (a leaf has height 1).
+pub async fn subtree_height(pool: &PgPool, task_id: Uuid) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar::<_, i32>(
+        r#"
+        WITH RECURSIVE subtree AS (
+            SELECT id, 1 AS depth FROM tasks WHERE id = $1
+            UNION ALL
+            SELECT t.id, s.depth + 1
+            FROM tasks t
+            JOIN subtree s ON t.parent_id = s.id
+            WHERE s.depth < $2
+        )
+        SELECT COALESCE(MAX(depth), 0) FROM subtree
+        "#,
+    )
+    .bind(task_id)
+    .bind(MAX_TASK_DEPTH + 1)
+    .fetch_one(pool)
+    .await
+}
+
+/// Ids of `task_id` and every task beneath it that isn't in the trash.
+pub async fn subtree_ids(pool: &PgPool, task_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        WITH RECURSIVE subtree AS (
+            SELECT id FROM tasks WHERE id = $1
+            UNION
+            SELECT t.id FROM tasks t JOIN subtree s ON t.parent_id = s.id WHERE t.deleted_at IS NULL
+        )
+        SELECT id FROM subtree
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// A parent task that was completed because all of its children are done.
+pub struct CompletedParent {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+    pub previous_status: TaskStatus,
+}
+
+/// Walks up from `task_id`, marking each ancestor done once all of its
+/// children are done. Stops at the first ancestor that still has open work
+/// or open blockers.
+pub async fn complete_finished_ancestors(conn: &mut PgConnection, task_id: Uuid) -> Result<Vec<CompletedParent>, sqlx::Error> {
+    let mut completed = Vec::new();
+    let mut current_id = task_id;
+
+    for _ in 0..MAX_TASK_DEPTH {
+        let parent = sqlx::query(
+            r#"
+            UPDATE tasks p
+            SET status = 'done'
+            FROM tasks c, tasks previous
+            WHERE c.id = $1
+                AND p.id = c.parent_id
+                AND previous.id = p.id
+                AND p.status != 'done'
+                AND NOT EXISTS (
+                    SELECT 1 FROM tasks s WHERE s.parent_id = p.id AND s.status != 'done' AND s.deleted_at IS NULL
+                )
+                -- Blocked parents can't finish, however done their children are
+                AND NOT EXISTS (
+                    SELECT 1 FROM task_dependencies d
+                    JOIN tasks b ON b.id = d.blocked_by_id
+                    WHERE d.task_id = p.id AND b.status != 'done' AND b.deleted_at IS NULL
+                )
+            RETURNING p.id, p.user_id, p.title, previous.status AS previous_status
+            "#,
+        )
+        .bind(current_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let Some(parent) = parent else { break };
+
+        current_id = parent.get("id");
+        completed.push(CompletedParent {
+            id: current_id,
+            user_id: parent.get("user_id"),
+            title: parent.get("title"),
+            previous_status: parent.get("previous_status"),
+        });
+    }
+
+    Ok(completed)
+}
@@ -0,0 +1,77 @@
+use chrono::{Duration, Utc};
+use serde_json::Map;
+use sqlx::{PgConnection, PgPool};
+use std::env;
+use uuid::Uuid;
+
+use crate::models::{Task, TaskEventType};
+use crate::services::task_events;
+
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+/// How long tasks stay in the trash, from TRASH_RETENTION_DAYS; 30 days for
+/// unset or invalid values
+pub fn retention_from_env() -> Duration {
+    let days = env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|&days| days > 0)
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+
+    Duration::days(days)
+}
+
+/// Ids of a trashed task and every trashed task beneath it
+pub async fn trashed_subtree_ids(pool: &PgPool, task_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        WITH RECURSIVE subtree AS (
+            SELECT id FROM tasks WHERE id = $1 AND deleted_at IS NOT NULL
+            UNION
+            SELECT t.id FROM tasks t JOIN subtree s ON t.parent_id = s.id WHERE t.deleted_at IS NOT NULL
+        )
+        SELECT id FROM subtree
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Deletes trashed tasks for good and records a purge event for each. Tasks
+/// that aren't in the trash are left alone. Attachment blobs are queued for
+/// `attachments::purge_deleted_blobs`.
+pub async fn purge(conn: &mut PgConnection, task_ids: &[Uuid], actor_id: Option<Uuid>) -> Result<Vec<Task>, sqlx::Error> {
+    let purged = sqlx::query_as::<_, Task>(
+        "DELETE FROM tasks WHERE id = ANY($1) AND deleted_at IS NOT NULL RETURNING *"
+    )
+    .bind(task_ids)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for task in &purged {
+        task_events::record(
+            &mut *conn, task.id, task.workspace_id, &task.title, actor_id, TaskEventType::Purged, Map::new(),
+        ).await?;
+    }
+
+    Ok(purged)
+}
+
+/// Purges tasks that have been in the trash for longer than `retention`.
+/// Returns how many were removed.
+pub async fn purge_expired(pool: &PgPool, retention: Duration) -> Result<usize, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let expired = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM tasks WHERE deleted_at < $1 FOR UPDATE SKIP LOCKED"
+    )
+    .bind(Utc::now() - retention)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let purged = purge(&mut tx, &expired, None).await?;
+
+    tx.commit().await?;
+    Ok(purged.len())
+}
@@ -0,0 +1,76 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub const PERSONAL_WORKSPACE_NAME: &str = "Personal";
+
+/// Creates a workspace owned by `owner_id`
+pub async fn create_workspace(pool: &PgPool, name: &str, owner_id: Uuid) -> Result<Uuid, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let workspace_id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO workspaces (name, owner_id) VALUES ($1, $2) RETURNING id"
+    )
+    .bind(name)
+    .bind(owner_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO workspace_members (workspace_id, user_id, role) VALUES ($1, $2, 'owner')")
+        .bind(workspace_id)
+        .bind(owner_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(workspace_id)
+}
+
+/// The user's personal workspace, created on first use
+pub async fn personal_workspace(pool: &PgPool, user_id: Uuid) -> Result<Uuid, sqlx::Error> {
+    let existing = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM workspaces WHERE owner_id = $1 AND personal"
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(workspace_id) = existing {
+        return Ok(workspace_id);
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let created = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO workspaces (name, owner_id, personal)
+        VALUES ($1, $2, TRUE)
+        ON CONFLICT (owner_id) WHERE personal DO NOTHING
+        RETURNING id
+        "#
+    )
+    .bind(PERSONAL_WORKSPACE_NAME)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let workspace_id = match created {
+        Some(workspace_id) => {
+            sqlx::query("INSERT INTO workspace_members (workspace_id, user_id, role) VALUES ($1, $2, 'owner')")
+                .bind(workspace_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+            workspace_id
+        }
+        // Created concurrently by another request
+        None => {
+            sqlx::query_scalar::<_, Uuid>("SELECT id FROM workspaces WHERE owner_id = $1 AND personal")
+                .bind(user_id)
+                .fetch_one(&mut *tx)
+                .await?
+        }
+    };
+
+    tx.commit().await?;
+    Ok(workspace_id)
+}
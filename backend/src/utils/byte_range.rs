@@ -0,0 +1,59 @@
+/// An inclusive range of bytes within a file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    pub fn length(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// What a `Range` header asks of a file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RangeRequest {
+    Full,
+    Partial(ByteRange),
+    // Answered with 416 Range Not Satisfiable
+    Unsatisfiable,
+}
+
+/// Interprets a `Range` header for a file of `size` bytes. Only single byte
+/// ranges are served partially; malformed, multi-part or non-byte ranges get
+/// the whole file, as RFC 9110 allows.
+pub fn parse_range(header: &str, size: u64) -> RangeRequest {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RangeRequest::Full;
+    };
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        // Suffix range: the last N bytes
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return RangeRequest::Unsatisfiable,
+            Ok(suffix) => (size.saturating_sub(suffix), size.saturating_sub(1)),
+            Err(_) => return RangeRequest::Full,
+        },
+        (start, "") => match start.parse::<u64>() {
+            Ok(start) => (start, size.saturating_sub(1)),
+            Err(_) => return RangeRequest::Full,
+        },
+        (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+            (Ok(start), Ok(end)) if start <= end => (start, end.min(size.saturating_sub(1))),
+            _ => return RangeRequest::Full,
+        },
+    };
+
+    if size == 0 || start >= size {
+        RangeRequest::Unsatisfiable
+    } else {
+        RangeRequest::Partial(ByteRange { start, end })
+    }
+}
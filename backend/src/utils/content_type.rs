@@ -0,0 +1,92 @@
+// Leading bytes of the binary formats recognised, with their media type
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\x7fELF", "application/x-executable"),
+    (b"\xfe\xed\xfa\xce", "application/x-mach-binary"),
+    (b"\xfe\xed\xfa\xcf", "application/x-mach-binary"),
+    (b"\xce\xfa\xed\xfe", "application/x-mach-binary"),
+    (b"\xcf\xfa\xed\xfe", "application/x-mach-binary"),
+];
+
+// Executables are never accepted, whatever they claim to be
+const BLOCKED: &[&str] = &["application/x-executable", "application/x-msdownload", "application/x-mach-binary"];
+
+// Zip-based document formats only distinguishable by their declared type
+const ZIP_BASED: &[&str] = &[
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "application/vnd.oasis.opendocument.text",
+    "application/vnd.oasis.opendocument.spreadsheet",
+];
+
+// Declared types kept as-is for content that sniffs as text
+const TEXT_BASED: &[&str] = &["application/json", "application/xml"];
+
+// Windows executables start with "MZ" and point to a "PE" header at 0x3c;
+// checking both avoids refusing text that happens to start with "MZ"
+fn is_portable_executable(data: &[u8]) -> bool {
+    if data.len() < 0x40 || !data.starts_with(b"MZ") {
+        return false;
+    }
+    let offset = u32::from_le_bytes([data[0x3c], data[0x3d], data[0x3e], data[0x3f]]) as usize;
+    data.get(offset..offset + 4) == Some(b"PE\0\0".as_slice())
+}
+
+/// Media type of `data` judging by its content alone: a known signature,
+/// `text/plain` for UTF-8 text, `application/octet-stream` otherwise.
+pub fn sniff(data: &[u8]) -> &'static str {
+    if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return "image/webp";
+    }
+    if is_portable_executable(data) {
+        return "application/x-msdownload";
+    }
+    if let Some((_, media_type)) = SIGNATURES.iter().find(|(magic, _)| data.starts_with(magic)) {
+        return media_type;
+    }
+
+    let is_text = std::str::from_utf8(data).is_ok_and(|text| !text.contains('\0'));
+    if is_text {
+        "text/plain"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+/// The media type to store for an upload, checking the client's declared type
+/// against the content. Errors describe why the file is refused.
+pub fn resolve(data: &[u8], declared: Option<&str>) -> Result<String, String> {
+    let sniffed = sniff(data);
+    if BLOCKED.contains(&sniffed) {
+        return Err("Executable files can't be attached".to_string());
+    }
+
+    let declared = declared
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty() && value != "application/octet-stream");
+
+    let Some(declared) = declared else {
+        return Ok(sniffed.to_string());
+    };
+
+    let compatible = declared == sniffed
+        || (sniffed == "application/zip" && ZIP_BASED.contains(&declared.as_str()))
+        || (sniffed == "text/plain" && (declared.starts_with("text/") || TEXT_BASED.contains(&declared.as_str())));
+
+    if compatible {
+        Ok(declared)
+    } else if declared.starts_with("image/") || declared.starts_with("video/") || declared == "application/pdf" {
+        Err(format!("File content doesn't match its declared type {}", declared))
+    } else {
+        // Unknown or generic claims fall back to what the content looks like
+        Ok(sniffed.to_string())
+    }
+}
@@ -0,0 +1,48 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Where a timestamp-ordered keyset page stopped; the id breaks ties
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Position<Id> {
+    pub at: DateTime<Utc>,
+    pub id: Id,
+}
+
+/// Encodes a keyset position as an opaque, URL-safe cursor
+pub fn encode<T: Serialize>(position: &T) -> String {
+    let json = serde_json::to_vec(position).expect("cursor positions serialize to JSON");
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+/// Decodes a cursor produced by `encode`
+pub fn decode<T: DeserializeOwned>(cursor: &str) -> Result<T, String> {
+    let json = URL_SAFE_NO_PAD
+        .decode(cursor.trim())
+        .map_err(|_| "Malformed cursor".to_string())?;
+    serde_json::from_slice(&json).map_err(|_| "Malformed cursor".to_string())
+}
+
+/// Reads an optional `cursor` parameter; a missing or blank one starts from the first page
+pub fn position<Id: DeserializeOwned>(cursor: Option<&str>) -> Result<Option<Position<Id>>, String> {
+    match cursor.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => decode(value).map(Some),
+    }
+}
+
+/// Trims rows fetched with `LIMIT limit + 1` back to `limit`, returning the
+/// cursor for the following page when the extra row shows there is one
+pub fn next_page<T, Id: Serialize>(
+    rows: &mut Vec<T>,
+    limit: i64,
+    key: impl FnOnce(&T) -> Option<(DateTime<Utc>, Id)>,
+) -> Option<String> {
+    if rows.len() as i64 <= limit {
+        return None;
+    }
+    rows.truncate(limit as usize);
+    rows.last().and_then(key).map(|(at, id)| encode(&Position { at, id }))
+}
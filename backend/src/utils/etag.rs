@@ -0,0 +1,60 @@
+use actix_web::http::header::{self, EntityTag, Header, IfMatch, IfNoneMatch};
+use actix_web::{HttpRequest, HttpResponse};
+use serde_json::json;
+
+use crate::utils::response::ApiError;
+
+/// The strong entity tag for a row at `version`
+pub fn entity_tag(version: i32) -> EntityTag {
+    EntityTag::new_strong(version.to_string())
+}
+
+/// Whether the request's `If-Match` lets a change to a row at `version` go
+/// ahead. Requests without the header always may; a malformed one never does.
+pub fn if_match(req: &HttpRequest, version: i32) -> bool {
+    if !req.headers().contains_key(header::IF_MATCH) {
+        return true;
+    }
+    match IfMatch::parse(req) {
+        Ok(IfMatch::Any) => true,
+        Ok(IfMatch::Items(tags)) => tags.iter().any(|tag| tag.strong_eq(&entity_tag(version))),
+        Err(_) => false,
+    }
+}
+
+/// Whether the request's `If-None-Match` shows the client already holds the
+/// row at `version`
+pub fn if_none_match(req: &HttpRequest, version: i32) -> bool {
+    match IfNoneMatch::parse(req) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&entity_tag(version))),
+        Err(_) => false,
+    }
+}
+
+/// Adds the `ETag` for `version` to a response
+pub fn with_etag(mut response: HttpResponse, version: i32) -> HttpResponse {
+    let value = entity_tag(version)
+        .to_string()
+        .parse()
+        .expect("entity tags are valid header values");
+    response.headers_mut().insert(header::ETAG, value);
+    response
+}
+
+pub fn not_modified_response(version: i32) -> HttpResponse {
+    HttpResponse::NotModified()
+        .insert_header(header::ETag(entity_tag(version)))
+        .finish()
+}
+
+/// 412 for a write made against a stale copy; carries the current version
+pub fn precondition_failed_response(version: i32) -> HttpResponse {
+    HttpResponse::PreconditionFailed()
+        .insert_header(header::ETag(entity_tag(version)))
+        .json(ApiError::with_fields(
+            "PRECONDITION_FAILED",
+            "The resource has changed since it was read",
+            json!({ "version": version }),
+        ))
+}
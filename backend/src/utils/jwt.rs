@@ -9,25 +9,34 @@ use actix_web::Result;
 pub struct Claims {
     pub sub: String,       // Subject (user email)
     pub user_id: String,   // User ID
+    pub sid: String,       // Session the token was issued for
     pub exp: i64,          // Expiration time
     pub iat: i64,          // Issued at
 }
 
-pub fn create_token(email: &str, user_id: &Uuid) -> Result<String> {
+/// Lifetime of access tokens, from JWT_EXPIRATION_MINUTES; 15 minutes by
+/// default. Clients keep going past it with a refresh token.
+pub fn access_token_ttl() -> Duration {
+    let minutes = env::var("JWT_EXPIRATION_MINUTES")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|&minutes| minutes > 0)
+        .unwrap_or(15);
+
+    Duration::minutes(minutes)
+}
+
+pub fn create_token(email: &str, user_id: &Uuid, session_id: &Uuid) -> Result<String> {
     let jwt_secret = env::var("JWT_SECRET")
         .expect("JWT_SECRET must be set");
-    
-    let expiration_hours = env::var("JWT_EXPIRATION_HOURS")
-        .unwrap_or_else(|_| "24".to_string())
-        .parse::<i64>()
-        .unwrap_or(24);
 
     let now = Utc::now();
-    let expiration = now + Duration::hours(expiration_hours);
+    let expiration = now + access_token_ttl();
 
     let claims = Claims {
         sub: email.to_string(),
         user_id: user_id.to_string(),
+        sid: session_id.to_string(),
         exp: expiration.timestamp(),
         iat: now.timestamp(),
     };
@@ -0,0 +1,62 @@
+// Characters around a mention kept in its excerpt
+const EXCERPT_BEFORE: usize = 40;
+const EXCERPT_AFTER: usize = 80;
+
+/// An `@handle` found in text, lowercased, with the char offset of its `@`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MentionHandle {
+    pub handle: String,
+    pub position: usize,
+}
+
+fn is_handle_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
+
+/// Finds the distinct `@handle`s in `text`, in order of first appearance.
+/// An `@` inside a word (as in an email address) doesn't start a mention.
+pub fn extract_mentions(text: &str) -> Vec<MentionHandle> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut mentions: Vec<MentionHandle> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] != '@' || (i > 0 && is_handle_char(chars[i - 1])) {
+            i += 1;
+            continue;
+        }
+
+        let end = (i + 1..chars.len())
+            .find(|&j| !is_handle_char(chars[j]))
+            .unwrap_or(chars.len());
+        // Sentence punctuation right after a handle isn't part of it
+        let handle = chars[i + 1..end]
+            .iter()
+            .collect::<String>()
+            .trim_end_matches(['.', '-'])
+            .to_lowercase();
+
+        if !handle.is_empty() && !mentions.iter().any(|m| m.handle == handle) {
+            mentions.push(MentionHandle { handle, position: i });
+        }
+        i = end.max(i + 1);
+    }
+
+    mentions
+}
+
+/// The text surrounding the mention at char offset `position`.
+pub fn excerpt(text: &str, position: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let start = position.saturating_sub(EXCERPT_BEFORE);
+    let end = (position + EXCERPT_AFTER).min(chars.len());
+
+    let mut excerpt = chars[start..end].iter().collect::<String>().trim().to_string();
+    if start > 0 {
+        excerpt.insert(0, '…');
+    }
+    if end < chars.len() {
+        excerpt.push('…');
+    }
+    excerpt
+}
@@ -1,3 +1,11 @@
 pub mod jwt;
 pub mod validation;
-pub mod response;
\ No newline at end of file
+pub mod response;
+pub mod recurrence;
+pub mod cursor;
+pub mod tokens;
+pub mod mentions;
+pub mod content_type;
+pub mod byte_range;
+pub mod sigv4;
+pub mod etag;
@@ -0,0 +1,240 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use std::fmt;
+
+// Upper bound on steps taken when catching a series up to the present
+const MAX_STEPS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Subset of an RFC 5545 RRULE: FREQ, INTERVAL, BYDAY (plain weekdays),
+/// UNTIL and COUNT. Occurrence indexes start at 1 for the first instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_weekday: Vec<Weekday>,
+    pub until: Option<DateTime<Utc>>,
+    pub count: Option<u32>,
+}
+
+impl RecurrenceRule {
+    /// Parses an RRULE value such as `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE`,
+    /// with or without a leading `RRULE:`.
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let rule = rule.trim();
+        let rule = rule
+            .strip_prefix("RRULE:")
+            .or_else(|| rule.strip_prefix("rrule:"))
+            .unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_weekday = Vec::new();
+        let mut until = None;
+        let mut count = None;
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Malformed RRULE part '{}'", part))?;
+
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => return Err(format!("Unsupported FREQ '{}'", other)),
+                    });
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| *interval >= 1)
+                        .ok_or("INTERVAL must be a positive integer")?;
+                }
+                "BYDAY" => {
+                    by_weekday = value
+                        .split(',')
+                        .map(parse_weekday)
+                        .collect::<Result<Vec<_>, _>>()?;
+                }
+                "UNTIL" => until = Some(parse_until(value)?),
+                "COUNT" => {
+                    count = Some(
+                        value
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|count| *count >= 1)
+                            .ok_or("COUNT must be a positive integer")?,
+                    );
+                }
+                "WKST" if value.eq_ignore_ascii_case("MO") => {}
+                other => return Err(format!("Unsupported RRULE part '{}'", other)),
+            }
+        }
+
+        let frequency = frequency.ok_or("FREQ is required")?;
+
+        if until.is_some() && count.is_some() {
+            return Err("UNTIL and COUNT cannot both be set".to_string());
+        }
+        if !by_weekday.is_empty() && !matches!(frequency, Frequency::Daily | Frequency::Weekly) {
+            return Err("BYDAY is only supported with DAILY or WEEKLY".to_string());
+        }
+
+        by_weekday.sort_by_key(|day| day.num_days_from_monday());
+        by_weekday.dedup();
+
+        Ok(Self { frequency, interval, by_weekday, until, count })
+    }
+
+    /// The occurrence following `current`, ignoring COUNT and UNTIL.
+    pub fn step(&self, current: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let interval = self.interval as i64;
+
+        match self.frequency {
+            Frequency::Daily if self.by_weekday.is_empty() => Some(current + Duration::days(interval)),
+            // An interval that's a multiple of 7 never changes weekday, so an
+            // anchor off the BYDAY days moves to the next one instead of ending
+            Frequency::Daily => (1..=7)
+                .map(|k| current + Duration::days(k * interval))
+                .chain((1..=7).map(|k| current + Duration::days(k)))
+                .find(|candidate| self.by_weekday.contains(&candidate.weekday())),
+            Frequency::Weekly if self.by_weekday.is_empty() => Some(current + Duration::weeks(interval)),
+            Frequency::Weekly => {
+                let current_day = current.weekday().num_days_from_monday();
+
+                // Remaining days this week, then the first day of the next active week
+                if let Some(day) = self
+                    .by_weekday
+                    .iter()
+                    .find(|day| day.num_days_from_monday() > current_day)
+                {
+                    return Some(current + Duration::days((day.num_days_from_monday() - current_day) as i64));
+                }
+
+                let week_start = current - Duration::days(current_day as i64);
+                let first_day = self.by_weekday[0].num_days_from_monday() as i64;
+                Some(week_start + Duration::weeks(interval) + Duration::days(first_day))
+            }
+            // Months or years without the anchor's day (e.g. the 31st) are skipped, per RFC 5545
+            Frequency::Monthly => (1..=12)
+                .find_map(|k| add_months(current, k * self.interval)),
+            Frequency::Yearly => (1..=8)
+                .find_map(|k| add_months(current, k * self.interval * 12)),
+        }
+    }
+
+    /// The first occurrence after `not_before`, stepping from the instance at
+    /// `current` with index `index`. Returns the occurrence and its index, or
+    /// `None` when the series has ended.
+    pub fn next_occurrence(
+        &self,
+        current: DateTime<Utc>,
+        index: i32,
+        not_before: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, i32)> {
+        let mut occurrence = current;
+        let mut index = index;
+
+        for _ in 0..MAX_STEPS {
+            occurrence = self.step(occurrence)?;
+            index += 1;
+
+            if self.count.is_some_and(|count| index > count as i32) {
+                return None;
+            }
+            if self.until.is_some_and(|until| occurrence > until) {
+                return None;
+            }
+            if occurrence > not_before {
+                return Some((occurrence, index));
+            }
+        }
+
+        None
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={};INTERVAL={}", frequency, self.interval)?;
+
+        if !self.by_weekday.is_empty() {
+            let days: Vec<&str> = self.by_weekday.iter().map(|day| weekday_code(*day)).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        other => Err(format!("Unsupported BYDAY value '{}'", other)),
+    }
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+// UNTIL is either a UTC date-time (`20261231T235959Z`) or a date (`20261231`)
+fn parse_until(value: &str) -> Result<DateTime<Utc>, String> {
+    let value = value.trim_end_matches('Z');
+
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Ok(Utc.from_utc_datetime(&datetime));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(23, 59, 59))
+        .map(|datetime| Utc.from_utc_datetime(&datetime))
+        .ok_or_else(|| format!("Invalid UNTIL value '{}'", value))
+}
+
+fn add_months(current: DateTime<Utc>, months: u32) -> Option<DateTime<Utc>> {
+    let total = current.month0() + months;
+    let year = current.year() + (total / 12) as i32;
+    let month = total % 12 + 1;
+
+    NaiveDate::from_ymd_opt(year, month, current.day())
+        .map(|date| Utc.from_utc_datetime(&date.and_time(current.time())))
+}
@@ -82,6 +82,10 @@ impl ApiError {
         Self::new("UNAUTHORIZED", message)
     }
 
+    pub fn forbidden(message: &str) -> Self {
+        Self::new("FORBIDDEN", message)
+    }
+
     pub fn conflict(message: &str) -> Self {
         Self::new("CONFLICT", message)
     }
@@ -100,8 +104,9 @@ impl fmt::Display for ApiError {
 impl ResponseError for ApiError {
     fn error_response(&self) -> HttpResponse {
         let status = match self.error.code.as_str() {
-            "VALIDATION_ERROR" => StatusCode::BAD_REQUEST,
+            "VALIDATION_ERROR" | "INVALID_CURSOR" => StatusCode::BAD_REQUEST,
             "UNAUTHORIZED" => StatusCode::UNAUTHORIZED,
+            "FORBIDDEN" => StatusCode::FORBIDDEN,
             "NOT_FOUND" => StatusCode::NOT_FOUND,
             "CONFLICT" => StatusCode::CONFLICT,
             "DATABASE_ERROR" => StatusCode::INTERNAL_SERVER_ERROR,
@@ -131,6 +136,10 @@ pub fn unauthorized_response(message: &str) -> HttpResponse {
     HttpResponse::Unauthorized().json(ApiError::unauthorized(message))
 }
 
+pub fn forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiError::forbidden(message))
+}
+
 pub fn not_found_response(resource: &str) -> HttpResponse {
     HttpResponse::NotFound().json(ApiError::not_found(resource))
 }
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+/// Payload hash of an empty body
+pub const EMPTY_PAYLOAD_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+/// Credentials and scope requests are signed with
+pub struct SigningKey<'a> {
+    pub access_key: &'a str,
+    pub secret_key: &'a str,
+    pub region: &'a str,
+    pub service: &'a str,
+}
+
+/// The parts of an HTTP request covered by an AWS Signature Version 4
+pub struct CanonicalRequest<'a> {
+    pub method: &'a str,
+    // URI-encoded path and canonical query string
+    pub path: &'a str,
+    pub query: &'a str,
+    // Signed headers; must include host and x-amz-date
+    pub headers: &'a [(&'a str, &'a str)],
+    pub payload_sha256: &'a str,
+}
+
+/// The `x-amz-date` header value for a signing time
+pub fn amz_date(timestamp: DateTime<Utc>) -> String {
+    timestamp.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Percent-encodes everything but unreserved characters, keeping `/` in paths
+pub fn uri_encode(value: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The `Authorization` header value for `request` signed at `timestamp`
+pub fn authorization(key: &SigningKey, request: &CanonicalRequest, timestamp: DateTime<Utc>) -> String {
+    let date = timestamp.format("%Y%m%d").to_string();
+    let scope = format!("{}/{}/{}/aws4_request", date, key.region, key.service);
+
+    let mut headers: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .map(|(name, value)| (name.to_lowercase(), value.trim().to_string()))
+        .collect();
+    headers.sort();
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+    let signed_headers = headers.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method, request.path, request.query, canonical_headers, signed_headers, request.payload_sha256
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date(timestamp),
+        scope,
+        sha256_hex(canonical_request.as_bytes())
+    );
+
+    let date_key = hmac_sha256(format!("AWS4{}", key.secret_key).as_bytes(), &date);
+    let region_key = hmac_sha256(&date_key, key.region);
+    let service_key = hmac_sha256(&region_key, key.service);
+    let signing_key = hmac_sha256(&service_key, "aws4_request");
+    let signature = hex::encode(hmac_sha256(&signing_key, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        key.access_key, scope, signed_headers, signature
+    )
+}
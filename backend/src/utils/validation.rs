@@ -14,4 +14,15 @@ pub fn validate_title_length(title: &str) -> bool {
 #[allow(dead_code)]
 pub fn validate_tags(tags: &[String]) -> bool {
     tags.len() <= 10 && tags.iter().all(|tag| !tag.is_empty() && tag.len() <= 50)
-}
\ No newline at end of file
+}
+// Colors are stored as #RRGGBB
+pub fn validate_hex_color(color: &str) -> Result<(), validator::ValidationError> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if valid {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("hex_color"))
+    }
+}
@@ -2,9 +2,11 @@ use actix_web::{test, web, App, http::StatusCode};
 use serde::Deserialize;
 use serde_json::json;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
 use taskmaster_backend::handlers::auth;
+use taskmaster_backend::services::mailer::{LogMailer, Mailer};
 
 // Mirror the ApiResponse shape used by the backend for JSON parsing in tests
 #[derive(Debug, Deserialize)]
@@ -38,8 +40,10 @@ async fn setup_test_db() -> PgPool {
 }
 
 fn create_app(pool: PgPool) -> App<impl actix_web::dev::ServiceFactory<actix_web::dev::ServiceRequest, Config = (), Response = actix_web::dev::ServiceResponse, Error = actix_web::Error, InitError = ()>> {
+    let mailer: Arc<dyn Mailer> = Arc::new(LogMailer);
     App::new()
         .app_data(web::Data::new(pool))
+        .app_data(web::Data::from(mailer))
         .service(auth::register)
         .service(auth::login)
         .service(auth::me)
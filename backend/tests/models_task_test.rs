@@ -1,7 +1,8 @@
 use taskmaster_backend::models::task::{
     Task, TaskStatus, TaskPriority, CreateTaskRequest, UpdateTaskRequest, 
-    TaskFilters, TasksResponse
+    PatchTaskRequest, TaskFilters, TasksResponse
 };
+use taskmaster_backend::models::task_filter::{FilterCondition, FilterValues, OrGroup, TagMatch};
 use chrono::Utc;
 use uuid::Uuid;
 use validator::Validate;
@@ -43,6 +44,7 @@ fn test_create_task_request_validation_valid() {
         priority: Some(TaskPriority::Med),
         due_date: Some(Utc::now()),
         tags: Some(vec!["work".to_string(), "urgent".to_string()]),
+        ..Default::default()
     };
 
     assert!(request.validate().is_ok());
@@ -57,6 +59,7 @@ fn test_create_task_request_validation_title_too_short() {
         priority: None,
         due_date: None,
         tags: None,
+        ..Default::default()
     };
 
     assert!(request.validate().is_err());
@@ -71,6 +74,7 @@ fn test_create_task_request_validation_title_too_long() {
         priority: None,
         due_date: None,
         tags: None,
+        ..Default::default()
     };
 
     assert!(request.validate().is_err());
@@ -85,6 +89,7 @@ fn test_create_task_request_validation_minimum_valid_title() {
         priority: None,
         due_date: None,
         tags: None,
+        ..Default::default()
     };
 
     assert!(request.validate().is_ok());
@@ -99,6 +104,7 @@ fn test_create_task_request_validation_maximum_valid_title() {
         priority: None,
         due_date: None,
         tags: None,
+        ..Default::default()
     };
 
     assert!(request.validate().is_ok());
@@ -113,6 +119,7 @@ fn test_update_task_request_validation_valid() {
         priority: Some(TaskPriority::High),
         due_date: Some(Utc::now()),
         tags: Some(vec!["updated".to_string()]),
+        ..Default::default()
     };
 
     assert!(request.validate().is_ok());
@@ -127,6 +134,7 @@ fn test_update_task_request_validation_title_too_short() {
         priority: None,
         due_date: None,
         tags: None,
+        ..Default::default()
     };
 
     assert!(request.validate().is_err());
@@ -141,6 +149,7 @@ fn test_update_task_request_validation_title_too_long() {
         priority: None,
         due_date: None,
         tags: None,
+        ..Default::default()
     };
 
     assert!(request.validate().is_err());
@@ -155,12 +164,31 @@ fn test_update_task_request_validation_all_none() {
         priority: None,
         due_date: None,
         tags: None,
+        ..Default::default()
     };
 
     // Should be valid even with all None values
     assert!(request.validate().is_ok());
 }
 
+#[test]
+fn test_patch_task_request_null_and_absent() {
+    let request: PatchTaskRequest = serde_json::from_str(r#"{ "description": null, "priority": "low" }"#).unwrap();
+    assert_eq!(request.description, Some(None), "null clears");
+    assert_eq!(request.due_date, None, "absent leaves alone");
+    assert_eq!(request.priority, Some(TaskPriority::Low));
+
+    let request: PatchTaskRequest = serde_json::from_str(r#"{ "due_date": "2030-01-01T00:00:00Z" }"#).unwrap();
+    assert!(matches!(request.due_date, Some(Some(_))));
+
+    // Required fields can't be cleared
+    assert!(serde_json::from_str::<PatchTaskRequest>(r#"{ "title": null }"#).is_err());
+    assert!(serde_json::from_str::<PatchTaskRequest>(r#"{ "status": null }"#).is_err());
+
+    let request: PatchTaskRequest = serde_json::from_str(r#"{ "title": "Hi" }"#).unwrap();
+    assert!(request.validate().is_err());
+}
+
 #[test]
 fn test_tasks_response_creation() {
     let task = Task {
@@ -174,21 +202,39 @@ fn test_tasks_response_creation() {
         tags: serde_json::json!(["test", "unit"]),
         created_at: Utc::now(),
         updated_at: Utc::now(),
+        parent_id: None,
+        recurrence_rule: None,
+        recurrence_series_id: None,
+        recurrence_index: None,
+        project_id: None,
+        workspace_id: Uuid::new_v4(),
+        assignee_id: None,
+        deleted_at: None,
+        archived_at: None,
+        completed_at: None,
+        version: 1,
+        position: 0.0,
+        subtasks_total: 0,
+        subtasks_done: 0,
+        is_blocked: false,
+        comment_count: 0,
     };
 
     let response = TasksResponse {
         tasks: vec![task],
-        total: 1,
+        total: Some(1),
         page: 1,
         limit: 10,
-        total_pages: 1,
+        total_pages: Some(1),
+        next_cursor: None,
+        highlights: None,
     };
 
     assert_eq!(response.tasks.len(), 1);
-    assert_eq!(response.total, 1);
+    assert_eq!(response.total, Some(1));
     assert_eq!(response.page, 1);
     assert_eq!(response.limit, 10);
-    assert_eq!(response.total_pages, 1);
+    assert_eq!(response.total_pages, Some(1));
 }
 
 #[test]
@@ -206,12 +252,43 @@ fn test_task_filters_deserialization() {
 
     let filters: TaskFilters = serde_json::from_str(json).unwrap();
     
-    assert_eq!(filters.status, Some(TaskStatus::Doing));
-    assert_eq!(filters.priority, Some(TaskPriority::High));
+    assert_eq!(filters.status, Some(FilterValues(vec![TaskStatus::Doing])));
+    assert_eq!(filters.priority, Some(FilterValues(vec![TaskPriority::High])));
     assert_eq!(filters.tag, Some("urgent".to_string()));
     assert_eq!(filters.search, Some("important".to_string()));
     assert_eq!(filters.page, Some(2));
     assert_eq!(filters.limit, Some(20));
     assert_eq!(filters.sort_by, Some("created_at".to_string()));
     assert_eq!(filters.sort_order, Some("desc".to_string()));
-}
\ No newline at end of file
+}
+#[test]
+fn test_task_filters_multiple_values_and_negation() {
+    let json = r#"{
+        "status": "todo,doing",
+        "priority!": "low",
+        "tags": "work, urgent",
+        "tags_mode": "all",
+        "or": "status:done|tags!:later"
+    }"#;
+
+    let filters: TaskFilters = serde_json::from_str(json).unwrap();
+
+    assert_eq!(filters.status, Some(FilterValues(vec![TaskStatus::Todo, TaskStatus::Doing])));
+    assert_eq!(filters.priority_not, Some(FilterValues(vec![TaskPriority::Low])));
+    assert_eq!(filters.tags, Some(FilterValues(vec!["work".to_string(), "urgent".to_string()])));
+    assert_eq!(filters.tags_mode, Some(TagMatch::All));
+    assert_eq!(
+        filters.or,
+        Some(OrGroup(vec![
+            FilterCondition::Status { values: vec![TaskStatus::Done], negated: false },
+            FilterCondition::Tags { values: vec!["later".to_string()], negated: true },
+        ]))
+    );
+}
+
+#[test]
+fn test_task_filters_reject_invalid_values() {
+    assert!(serde_json::from_str::<TaskFilters>(r#"{ "status": "todo,archived" }"#).is_err());
+    assert!(serde_json::from_str::<TaskFilters>(r#"{ "or": "owner:me" }"#).is_err());
+    assert!(serde_json::from_str::<TaskFilters>(r#"{ "or": "status" }"#).is_err());
+}
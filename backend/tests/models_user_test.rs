@@ -12,6 +12,9 @@ fn test_user_response_from_user() {
         password_hash: "hashed_password".to_string(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
+        auto_archive_days: Some(30),
+        email_verified_at: None,
+        deleted_at: None,
     };
 
     let user_id = user.id;
@@ -25,6 +28,7 @@ fn test_user_response_from_user() {
     assert_eq!(response.name, user_name);
     assert_eq!(response.email, user_email);
     assert_eq!(response.created_at, user_created_at);
+    assert_eq!(response.email_verified_at, None);
 }
 
 #[test]
@@ -0,0 +1,108 @@
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+use taskmaster_backend::services::mailer::{Email, FileMailer, MailError, Mailer, SmtpMailer, SmtpSecurity};
+
+type Received = Arc<Mutex<Vec<String>>>;
+
+// Minimal SMTP sink: accepts every message without TLS or authentication and
+// keeps the envelope recipients and DATA of each
+async fn start_smtp_sink() -> (u16, Received) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let port = listener.local_addr().unwrap().port();
+    let received = Received::default();
+    let sink = received.clone();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let received = sink.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                let mut message = String::new();
+                let mut in_data = false;
+
+                writer.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if in_data {
+                        if line == "." {
+                            in_data = false;
+                            received.lock().unwrap().push(std::mem::take(&mut message));
+                            writer.write_all(b"250 queued\r\n").await.unwrap();
+                        } else {
+                            message.push_str(&line);
+                            message.push('\n');
+                        }
+                        continue;
+                    }
+
+                    let command = line.to_uppercase();
+                    let reply: &[u8] = if command.starts_with("EHLO") || command.starts_with("HELO") {
+                        b"250 sink\r\n"
+                    } else if command.starts_with("RCPT TO") {
+                        message.push_str(&format!("{}\n", line));
+                        b"250 ok\r\n"
+                    } else if command.starts_with("DATA") {
+                        in_data = true;
+                        b"354 go ahead\r\n"
+                    } else if command.starts_with("QUIT") {
+                        let _ = writer.write_all(b"221 bye\r\n").await;
+                        break;
+                    } else {
+                        b"250 ok\r\n"
+                    };
+                    writer.write_all(reply).await.unwrap();
+                }
+            });
+        }
+    });
+
+    (port, received)
+}
+
+fn welcome() -> Email {
+    Email {
+        to: "someone@example.com".to_string(),
+        subject: "Welcome aboard".to_string(),
+        body: "Hello from the test suite".to_string(),
+    }
+}
+
+#[actix_rt::test]
+async fn test_file_mailer() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let mailer = FileMailer::new(dir.path().join("mail"));
+
+    mailer.send(&welcome()).await.expect("send");
+    mailer.send(&welcome()).await.expect("send");
+
+    let mut files: Vec<_> = std::fs::read_dir(dir.path().join("mail")).unwrap().map(|entry| entry.unwrap().path()).collect();
+    files.sort();
+    assert_eq!(files.len(), 2, "each email gets its own file");
+    let contents = std::fs::read_to_string(&files[0]).unwrap();
+    assert!(contents.contains("To: someone@example.com"));
+    assert!(contents.contains("Subject: Welcome aboard"));
+    assert!(contents.contains("Hello from the test suite"));
+}
+
+#[actix_rt::test]
+async fn test_smtp_mailer_against_sink() {
+    let (port, received) = start_smtp_sink().await;
+    let mailer = SmtpMailer::new("127.0.0.1", Some(port), SmtpSecurity::None, None, "Taskmaster <no-reply@example.com>")
+        .expect("mailer");
+
+    mailer.send(&welcome()).await.expect("send");
+
+    let message = {
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        received[0].clone()
+    };
+    assert!(message.contains("<someone@example.com>"));
+    assert!(message.contains("Subject: Welcome aboard"));
+    assert!(message.contains("Hello from the test suite"));
+
+    let invalid = Email { to: "not an address".to_string(), ..welcome() };
+    assert!(matches!(mailer.send(&invalid).await, Err(MailError::Address(_))));
+}
@@ -76,11 +76,16 @@ async fn test_helper_notifications_builders() {
     let n1 = service.notify_task_due_soon(user_id, "Task A", 2).await.expect("due soon");
     assert_eq!(n1.notification_type, "task_due_soon");
 
-    let n2 = service.notify_task_assigned(user_id, "Task B", 42).await.expect("assigned");
+    let n2 = service.notify_task_assigned(user_id, "Task B", Uuid::new_v4()).await.expect("assigned");
     assert_eq!(n2.notification_type, "task_assigned");
 
-    let n3 = service.notify_task_completed(user_id, "Task C", 7).await.expect("completed");
+    let n5 = service.notify_task_unassigned(user_id, "Task B", Uuid::new_v4()).await.expect("unassigned");
+    assert_eq!(n5.notification_type, "task_unassigned");
+
+    let completed_id = Uuid::new_v4();
+    let n3 = service.notify_task_completed(user_id, "Task C", completed_id).await.expect("completed");
     assert_eq!(n3.notification_type, "task_completed");
+    assert_eq!(n3.metadata["task_id"], completed_id.to_string());
 
     let n4 = service.notify_task_overdue(user_id, "Task D", 3).await.expect("overdue");
     assert_eq!(n4.notification_type, "task_overdue");
@@ -37,3 +37,342 @@ async fn test_scheduler_new_and_run_checks_now() {
     // Can run checks on empty DB without errors
     scheduler.run_checks_now().await.expect("run_checks_now");
 }
+
+#[actix_rt::test]
+async fn test_scheduler_generates_lapsed_recurring_instances() {
+    use taskmaster_backend::services::notification_service::NotificationService;
+    use taskmaster_backend::services::scheduler::TaskScheduler;
+
+    let pool = test_pool().await;
+    let user_id = Uuid::new_v4();
+    ensure_user_exists(&pool, user_id, &format!("scheduler-recurring-{}@example.com", user_id)).await;
+
+    let workspace_id = taskmaster_backend::services::workspaces::personal_workspace(&pool, user_id)
+        .await
+        .expect("personal workspace");
+
+    // A daily task that was never completed and is now two days overdue
+    let series_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (id, user_id, workspace_id, title, due_date, recurrence_rule, recurrence_series_id, recurrence_index)
+        VALUES ($1, $2, $3, 'Water plants', NOW() - INTERVAL '2 days', 'FREQ=DAILY;INTERVAL=1', $1, 1)
+        "#
+    )
+    .bind(series_id)
+    .bind(user_id)
+    .bind(workspace_id)
+    .execute(&pool)
+    .await
+    .expect("insert recurring task");
+
+    let service = NotificationService::new(pool.clone());
+    let scheduler = TaskScheduler::new(pool.clone(), service);
+    scheduler.run_checks_now().await.expect("run_checks_now");
+    // Running again must not duplicate the instance
+    scheduler.run_checks_now().await.expect("run_checks_now again");
+
+    let instances: Vec<(i32, chrono::DateTime<Utc>)> = sqlx::query_as(
+        "SELECT recurrence_index, due_date FROM tasks WHERE recurrence_series_id = $1 AND id <> $1"
+    )
+    .bind(series_id)
+    .fetch_all(&pool)
+    .await
+    .expect("fetch instances");
+
+    assert_eq!(instances.len(), 1);
+    let (index, due_date) = instances[0];
+    assert_eq!(index, 4);
+    assert!(due_date > Utc::now());
+
+    // The new instance's history starts with its creation, by no one in particular
+    let created: Vec<Option<Uuid>> = sqlx::query_scalar(
+        r#"
+        SELECT actor_id FROM task_events
+        WHERE event_type = 'created' AND task_id IN (SELECT id FROM tasks WHERE recurrence_series_id = $1 AND id <> $1)
+        "#
+    )
+    .bind(series_id)
+    .fetch_all(&pool)
+    .await
+    .expect("fetch events");
+    assert_eq!(created, vec![None]);
+}
+
+#[actix_rt::test]
+async fn test_scheduler_purges_expired_trash() {
+    use taskmaster_backend::services::notification_service::NotificationService;
+    use taskmaster_backend::services::scheduler::TaskScheduler;
+
+    let pool = test_pool().await;
+    let user_id = Uuid::new_v4();
+    ensure_user_exists(&pool, user_id, &format!("scheduler-trash-{}@example.com", user_id)).await;
+
+    let workspace_id = taskmaster_backend::services::workspaces::personal_workspace(&pool, user_id)
+        .await
+        .expect("personal workspace");
+
+    let (expired, recent) = (Uuid::new_v4(), Uuid::new_v4());
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (id, user_id, workspace_id, title, deleted_at)
+        VALUES ($1, $3, $4, 'Long gone', NOW() - INTERVAL '8 days'),
+               ($2, $3, $4, 'Just deleted', NOW() - INTERVAL '1 day')
+        "#
+    )
+    .bind(expired)
+    .bind(recent)
+    .bind(user_id)
+    .bind(workspace_id)
+    .execute(&pool)
+    .await
+    .expect("insert trashed tasks");
+
+    let service = NotificationService::new(pool.clone());
+    let scheduler = TaskScheduler::new(pool.clone(), service).with_trash_retention(chrono::Duration::days(7));
+    scheduler.run_checks_now().await.expect("run_checks_now");
+
+    let remaining: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM tasks WHERE id = ANY($1)")
+        .bind(vec![expired, recent])
+        .fetch_all(&pool)
+        .await
+        .expect("fetch tasks");
+    assert_eq!(remaining, vec![recent]);
+
+    let purge_actor: Option<Option<Uuid>> = sqlx::query_scalar(
+        "SELECT actor_id FROM task_events WHERE task_id = $1 AND event_type = 'purged'"
+    )
+    .bind(expired)
+    .fetch_optional(&pool)
+    .await
+    .expect("fetch purge event");
+    assert_eq!(purge_actor, Some(None), "purges by the scheduler have no actor");
+}
+
+#[actix_rt::test]
+async fn test_scheduler_archives_finished_tasks() {
+    use taskmaster_backend::services::notification_service::NotificationService;
+    use taskmaster_backend::services::scheduler::TaskScheduler;
+
+    let pool = test_pool().await;
+    let user_id = Uuid::new_v4();
+    ensure_user_exists(&pool, user_id, &format!("scheduler-archive-{}@example.com", user_id)).await;
+    sqlx::query("UPDATE users SET auto_archive_days = 7 WHERE id = $1")
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .expect("set auto_archive_days");
+
+    let workspace_id = taskmaster_backend::services::workspaces::personal_workspace(&pool, user_id)
+        .await
+        .expect("personal workspace");
+
+    let (stale, recent, unarchived) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (id, user_id, workspace_id, title, status)
+        VALUES ($1, $4, $5, 'Done long ago', 'done'),
+               ($2, $4, $5, 'Done yesterday', 'done'),
+               ($3, $4, $5, 'Kept on purpose', 'done')
+        "#
+    )
+    .bind(stale)
+    .bind(recent)
+    .bind(unarchived)
+    .bind(user_id)
+    .bind(workspace_id)
+    .execute(&pool)
+    .await
+    .expect("insert done tasks");
+
+    sqlx::query(
+        r#"
+        UPDATE tasks SET completed_at = CASE WHEN id = $2 THEN NOW() - INTERVAL '1 day' ELSE NOW() - INTERVAL '10 days' END
+        WHERE id = ANY($1)
+        "#
+    )
+    .bind(vec![stale, recent, unarchived])
+    .bind(recent)
+    .execute(&pool)
+    .await
+    .expect("backdate completion");
+
+    // Unarchived by hand after it was completed
+    sqlx::query(
+        r#"
+        INSERT INTO task_events (task_id, workspace_id, actor_id, event_type, task_title)
+        VALUES ($1, $2, $3, 'unarchived', 'Kept on purpose')
+        "#
+    )
+    .bind(unarchived)
+    .bind(workspace_id)
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .expect("insert unarchive event");
+
+    let service = NotificationService::new(pool.clone());
+    let scheduler = TaskScheduler::new(pool.clone(), service);
+    scheduler.run_checks_now().await.expect("run_checks_now");
+
+    let archived: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM tasks WHERE id = ANY($1) AND archived_at IS NOT NULL")
+        .bind(vec![stale, recent, unarchived])
+        .fetch_all(&pool)
+        .await
+        .expect("fetch archived");
+    assert_eq!(archived, vec![stale]);
+}
+
+#[actix_rt::test]
+async fn test_scheduler_skips_archived_tasks_when_reminding() {
+    use taskmaster_backend::services::notification_service::NotificationService;
+    use taskmaster_backend::services::scheduler::TaskScheduler;
+
+    let pool = test_pool().await;
+    let user_id = Uuid::new_v4();
+    ensure_user_exists(&pool, user_id, &format!("scheduler-reminders-{}@example.com", user_id)).await;
+    let workspace_id = taskmaster_backend::services::workspaces::personal_workspace(&pool, user_id)
+        .await
+        .expect("personal workspace");
+
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (user_id, workspace_id, title, due_date, archived_at)
+        VALUES ($1, $2, 'Archived and overdue', NOW() - INTERVAL '1 day', NOW()),
+               ($1, $2, 'Archived and due soon', NOW() + INTERVAL '1 hour', NOW()),
+               ($1, $2, 'Overdue', NOW() - INTERVAL '1 day', NULL)
+        "#
+    )
+    .bind(user_id)
+    .bind(workspace_id)
+    .execute(&pool)
+    .await
+    .expect("insert tasks");
+
+    let service = NotificationService::new(pool.clone());
+    let scheduler = TaskScheduler::new(pool.clone(), service);
+    scheduler.run_checks_now().await.expect("run_checks_now");
+
+    let messages: Vec<String> = sqlx::query_scalar(
+        // Other tests' scheduler runs may remind about the same task again
+        "SELECT DISTINCT message FROM notifications WHERE user_id = $1 AND notification_type IN ('task_overdue', 'task_due_soon')"
+    )
+    .bind(user_id)
+    .fetch_all(&pool)
+    .await
+    .expect("fetch notifications");
+    assert_eq!(messages, vec!["'Overdue' is now overdue".to_string()]);
+}
+
+#[actix_rt::test]
+async fn test_scheduler_purges_deleted_accounts() {
+    use taskmaster_backend::services::notification_service::NotificationService;
+    use taskmaster_backend::services::scheduler::TaskScheduler;
+
+    let pool = test_pool().await;
+    let (expired, recent) = (Uuid::new_v4(), Uuid::new_v4());
+    ensure_user_exists(&pool, expired, &format!("scheduler-deleted-{}@example.com", expired)).await;
+    ensure_user_exists(&pool, recent, &format!("scheduler-deleted-{}@example.com", recent)).await;
+
+    let workspace_id = taskmaster_backend::services::workspaces::personal_workspace(&pool, expired)
+        .await
+        .expect("personal workspace");
+
+    // History the deleted user left in someone else's workspace stays, without them
+    let other = Uuid::new_v4();
+    ensure_user_exists(&pool, other, &format!("scheduler-deleted-{}@example.com", other)).await;
+    let other_workspace_id = taskmaster_backend::services::workspaces::personal_workspace(&pool, other)
+        .await
+        .expect("other workspace");
+    let other_task_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO task_events (task_id, workspace_id, actor_id, event_type, task_title)
+        VALUES ($1, $2, $3, 'updated', 'Shared work')
+        "#
+    )
+    .bind(other_task_id)
+    .bind(other_workspace_id)
+    .bind(expired)
+    .execute(&pool)
+    .await
+    .expect("insert event");
+
+    // Work they created there is kept too, handed to the workspace owner
+    let shared_task_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO tasks (id, user_id, workspace_id, title) VALUES ($1, $2, $3, 'Shared work')")
+        .bind(shared_task_id)
+        .bind(expired)
+        .bind(other_workspace_id)
+        .execute(&pool)
+        .await
+        .expect("insert shared task");
+    let comment_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO task_comments (task_id, user_id, body) VALUES ($1, $2, 'Still relevant') RETURNING id"
+    )
+    .bind(shared_task_id)
+    .bind(expired)
+    .fetch_one(&pool)
+    .await
+    .expect("insert comment");
+
+    let task_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO tasks (id, user_id, workspace_id, title) VALUES ($1, $2, $3, 'Left behind')")
+        .bind(task_id)
+        .bind(expired)
+        .bind(workspace_id)
+        .execute(&pool)
+        .await
+        .expect("insert task");
+
+    sqlx::query(
+        r#"
+        UPDATE users SET deleted_at = CASE WHEN id = $1 THEN NOW() - INTERVAL '8 days' ELSE NOW() - INTERVAL '1 day' END
+        WHERE id = ANY($2)
+        "#
+    )
+    .bind(expired)
+    .bind(vec![expired, recent])
+    .execute(&pool)
+    .await
+    .expect("delete accounts");
+
+    let service = NotificationService::new(pool.clone());
+    let scheduler = TaskScheduler::new(pool.clone(), service).with_account_deletion_grace(chrono::Duration::days(7));
+    scheduler.run_checks_now().await.expect("run_checks_now");
+
+    let remaining: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM users WHERE id = ANY($1)")
+        .bind(vec![expired, recent])
+        .fetch_all(&pool)
+        .await
+        .expect("fetch users");
+    assert_eq!(remaining, vec![recent]);
+
+    let task_left: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM tasks WHERE id = $1)")
+        .bind(task_id)
+        .fetch_one(&pool)
+        .await
+        .expect("fetch task");
+    assert!(!task_left, "tasks go with the account");
+
+    let actors: Vec<Option<Uuid>> = sqlx::query_scalar("SELECT actor_id FROM task_events WHERE task_id = $1")
+        .bind(other_task_id)
+        .fetch_all(&pool)
+        .await
+        .expect("fetch events");
+    assert_eq!(actors, vec![None]);
+
+    let shared_owner: Uuid = sqlx::query_scalar("SELECT user_id FROM tasks WHERE id = $1")
+        .bind(shared_task_id)
+        .fetch_one(&pool)
+        .await
+        .expect("fetch shared task");
+    assert_eq!(shared_owner, other);
+
+    let comment_author: Option<Uuid> = sqlx::query_scalar("SELECT user_id FROM task_comments WHERE id = $1")
+        .bind(comment_id)
+        .fetch_one(&pool)
+        .await
+        .expect("fetch comment");
+    assert_eq!(comment_author, None);
+}
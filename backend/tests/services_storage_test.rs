@@ -0,0 +1,110 @@
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use bytes::Bytes;
+use futures_util::TryStreamExt;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::sync::Mutex;
+
+use taskmaster_backend::services::storage::{LocalStorage, S3Storage, StorageBackend, StorageError};
+use taskmaster_backend::utils::byte_range::ByteRange;
+
+const ACCESS_KEY: &str = "stand-in-access-key";
+
+type Objects = Mutex<HashMap<String, Vec<u8>>>;
+
+// Minimal S3-compatible stand-in: path-style PUT/GET/DELETE of objects,
+// single byte ranges, and a check that requests are signed
+async fn s3_object(req: HttpRequest, body: web::Bytes, objects: web::Data<Objects>) -> HttpResponse {
+    let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+
+    let credential = format!("AWS4-HMAC-SHA256 Credential={}/", ACCESS_KEY);
+    if !header("authorization").starts_with(&credential) || header("x-amz-date").is_empty() {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let key = req.path().to_string();
+    let mut objects = objects.lock().unwrap();
+
+    match req.method().as_str() {
+        "PUT" => {
+            if header("x-amz-content-sha256") != hex::encode(Sha256::digest(&body)) {
+                return HttpResponse::BadRequest().body("XAmzContentSHA256Mismatch");
+            }
+            objects.insert(key, body.to_vec());
+            HttpResponse::Ok().finish()
+        }
+        "GET" => {
+            let Some(data) = objects.get(&key) else {
+                return HttpResponse::NotFound().body("NoSuchKey");
+            };
+            match header("range").strip_prefix("bytes=").and_then(|r| r.split_once('-')) {
+                Some((start, end)) => {
+                    let (start, end): (usize, usize) = (start.parse().unwrap(), end.parse().unwrap());
+                    HttpResponse::PartialContent().body(data[start..=end].to_vec())
+                }
+                None => HttpResponse::Ok().body(data.clone()),
+            }
+        }
+        "DELETE" => {
+            objects.remove(&key);
+            HttpResponse::NoContent().finish()
+        }
+        _ => HttpResponse::MethodNotAllowed().finish(),
+    }
+}
+
+fn start_s3_stand_in() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().unwrap();
+    let objects = web::Data::new(Objects::default());
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(objects.clone())
+            .default_service(web::route().to(s3_object))
+    })
+    .listen(listener)
+    .expect("listen")
+    .run();
+    actix_rt::spawn(server);
+
+    format!("http://{}", addr)
+}
+
+async fn read_all(storage: &dyn StorageBackend, key: &str, range: Option<ByteRange>) -> Vec<u8> {
+    let chunks: Vec<Bytes> = storage.get(key, range).await.expect("get").try_collect().await.expect("read");
+    chunks.concat()
+}
+
+// Put, full and ranged reads, and idempotent deletes behave the same on every backend
+async fn exercise_backend(storage: &dyn StorageBackend) {
+    let key = format!("tasks/{}/blob", uuid::Uuid::new_v4());
+    let data = Bytes::from_static(b"0123456789abcdef");
+
+    storage.put(&key, data.clone(), "text/plain").await.expect("put");
+    assert_eq!(read_all(storage, &key, None).await, data.to_vec());
+    assert_eq!(read_all(storage, &key, Some(ByteRange { start: 10, end: 15 })).await, b"abcdef");
+
+    storage.delete(&key).await.expect("delete");
+    assert!(matches!(storage.get(&key, None).await, Err(StorageError::NotFound)));
+    storage.delete(&key).await.expect("deleting a missing blob succeeds");
+}
+
+#[actix_rt::test]
+async fn test_local_storage() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let storage = LocalStorage::new(dir.path());
+    exercise_backend(&storage).await;
+}
+
+#[actix_rt::test]
+async fn test_s3_storage_against_stand_in() {
+    let endpoint = start_s3_stand_in();
+    let storage = S3Storage::new(&endpoint, "attachments", "us-east-1", ACCESS_KEY, "stand-in-secret");
+    exercise_backend(&storage).await;
+
+    let unsigned = S3Storage::new(&endpoint, "attachments", "us-east-1", "someone-else", "secret");
+    let result = unsigned.put("tasks/x/blob", Bytes::from_static(b"data"), "text/plain").await;
+    assert!(matches!(result, Err(StorageError::Backend(_))));
+}
@@ -2,11 +2,16 @@ use actix_web::{test, web, App, http::StatusCode};
 use serde::Deserialize;
 use serde_json::json;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 use taskmaster_backend::handlers::{auth, tasks, stats, notifications};
 use taskmaster_backend::models::{notification::NotificationResponse};
 use taskmaster_backend::services::notification_service::NotificationService;
+use taskmaster_backend::services::storage::{LocalStorage, StorageBackend};
 use taskmaster_backend::handlers::health_check;
+use taskmaster_backend::services::sessions::{self, SessionClient};
+use taskmaster_backend::utils::jwt::{create_token, verify_token};
+use taskmaster_backend::services::mailer::{LogMailer, Mailer};
 
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
@@ -21,9 +26,13 @@ async fn setup_test_db() -> PgPool {
 
 fn create_app(pool: PgPool) -> App<impl actix_web::dev::ServiceFactory<actix_web::dev::ServiceRequest, Config = (), Response = actix_web::dev::ServiceResponse, Error = actix_web::Error, InitError = ()>> {
     let notification_service = NotificationService::new(pool.clone());
+    let storage: Arc<dyn StorageBackend> = Arc::new(LocalStorage::new(std::env::temp_dir().join("taskmaster_test_attachments")));
+    let mailer: Arc<dyn Mailer> = Arc::new(LogMailer);
     App::new()
         .app_data(web::Data::new(pool))
         .app_data(web::Data::new(notification_service))
+        .app_data(web::Data::from(mailer))
+        .app_data(web::Data::from(storage))
         // auth
         .service(auth::register)
         // tasks under /tasks
@@ -97,27 +106,19 @@ async fn test_stats_get_task_statistics() {
     let _body: ApiResponse<serde_json::Value> = test::read_body_json(resp).await;
 }
 
-#[actix_web::test]
-async fn test_notifications_websocket_auth_and_receive() {
-    use awc::ws;
-    use futures_util::{StreamExt, SinkExt};
-    use actix_web::{App, HttpServer};
+// Starts a server exposing only the WS route; returns the ws:// URL
+fn start_ws_server(notification_service: NotificationService) -> String {
+    use actix_web::HttpServer;
     use std::net::TcpListener;
 
-    // Service + server with only WS route
-    let pool = setup_test_db().await;
-    let notification_service = NotificationService::new(pool.clone());
-    let ns_for_app = notification_service.clone();
-
     // Bind ephemeral port
     let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
     let addr = listener.local_addr().unwrap();
-    let ws_url = format!("ws://{}/ws/notifications", addr);
 
     // Start server in background
     let server = HttpServer::new(move || {
         App::new()
-            .app_data(web::Data::new(ns_for_app.clone()))
+            .app_data(web::Data::new(notification_service.clone()))
             .route("/ws/notifications", web::get().to(notifications::websocket))
     })
     .listen(listener)
@@ -125,6 +126,53 @@ async fn test_notifications_websocket_auth_and_receive() {
     .run();
     actix_rt::spawn(server);
 
+    format!("ws://{}/ws/notifications", addr)
+}
+
+// Inserts a user directly and returns its id plus a valid token
+async fn create_ws_user(pool: &PgPool) -> (Uuid, String) {
+    let user_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO users (id, name, email, password_hash) VALUES ($1, 'WS User', $2, 'x')")
+        .bind(user_id)
+        .bind(format!("ws_user_{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .expect("insert user");
+    let client = SessionClient { user_agent: None, ip_address: None };
+    let (session_id, _) = sessions::create(pool, user_id, &client).await.expect("session");
+    let token = create_token("ws@example.com", &user_id, &session_id).expect("token");
+    (user_id, token)
+}
+
+// Reads text frames until one contains `needle` or the socket closes
+async fn wait_for_text<S>(framed: &mut S, needle: &str) -> bool
+where
+    S: futures_util::Stream<Item = Result<awc::ws::Frame, awc::error::WsProtocolError>> + Unpin,
+{
+    use futures_util::StreamExt;
+
+    for _ in 0..20 {
+        match framed.next().await {
+            Some(Ok(awc::ws::Frame::Text(txt))) => {
+                if String::from_utf8_lossy(&txt).contains(needle) { return true; }
+            }
+            Some(Ok(_)) => {}
+            _ => return false,
+        }
+    }
+    false
+}
+
+#[actix_web::test]
+async fn test_notifications_websocket_auth_and_receive() {
+    use awc::ws;
+    use futures_util::SinkExt;
+
+    let pool = setup_test_db().await;
+    let notification_service = NotificationService::new(pool.clone());
+    let ws_url = start_ws_server(notification_service.clone());
+    let (user_id, token) = create_ws_user(&pool).await;
+
     // Connect WS client
     let (_resp, mut framed) = awc::Client::new()
         .ws(ws_url)
@@ -133,44 +181,120 @@ async fn test_notifications_websocket_auth_and_receive() {
         .expect("ws connect");
 
     // Send auth message
-    let user_id = Uuid::new_v4();
-    let auth_msg = format!("{{\"user_id\":\"{}\"}}", user_id);
+    let auth_msg = json!({ "type": "auth", "token": token }).to_string();
     framed.send(ws::Message::Text(auth_msg.into())).await.expect("send auth");
 
     // Expect authenticated ack
-    let mut authed = false;
-    for _ in 0..10 {
-        if let Some(Ok(frame)) = framed.next().await {
-            if let ws::Frame::Text(txt) = frame {
-                let s = String::from_utf8_lossy(&txt);
-                if s.contains("authenticated") { authed = true; break; }
-            }
-        }
-    }
-    assert!(authed, "should receive auth ack");
+    assert!(wait_for_text(&mut framed, "\"type\":\"authenticated\"").await, "should receive auth ack");
 
     // Broadcast a notification
     notification_service
-        .notify_task_assigned(user_id, "WS Task", 1)
+        .notify_task_assigned(user_id, "WS Task", Uuid::new_v4())
         .await
         .expect("notify");
 
     // Receive notification frame
-    let mut got_notification = false;
-    for _ in 0..20 {
-        if let Some(Ok(frame)) = framed.next().await {
-            if let ws::Frame::Text(txt) = frame {
-                let s = String::from_utf8_lossy(&txt);
-                if s.contains("\"type\":\"notification\"") { got_notification = true; break; }
-            }
+    assert!(wait_for_text(&mut framed, "\"type\":\"notification\"").await, "should receive notification via WS");
+}
+
+#[actix_web::test]
+async fn test_notifications_websocket_query_token() {
+    let pool = setup_test_db().await;
+    let notification_service = NotificationService::new(pool.clone());
+    let ws_url = start_ws_server(notification_service);
+    let (_user_id, token) = create_ws_user(&pool).await;
+
+    let (_resp, mut framed) = awc::Client::new()
+        .ws(format!("{}?token={}", ws_url, token))
+        .connect()
+        .await
+        .expect("ws connect");
+
+    assert!(wait_for_text(&mut framed, "\"type\":\"authenticated\"").await, "handshake token should authenticate");
+}
+
+#[actix_web::test]
+async fn test_notifications_websocket_closed_when_session_revoked() {
+    let pool = setup_test_db().await;
+    let notification_service = NotificationService::new(pool.clone());
+    let ws_url = start_ws_server(notification_service.clone());
+    let (user_id, token) = create_ws_user(&pool).await;
+    let session_id: Uuid = verify_token(&token).unwrap().sid.parse().unwrap();
+
+    let (_resp, mut framed) = awc::Client::new()
+        .ws(format!("{}?token={}", ws_url, token))
+        .connect()
+        .await
+        .expect("ws connect");
+    assert!(wait_for_text(&mut framed, "\"type\":\"authenticated\"").await, "handshake token should authenticate");
+
+    // Revoking another session leaves the socket open
+    notification_service.close_sessions(user_id, &[Uuid::new_v4()]).await;
+    assert!(sessions::revoke(&pool, user_id, session_id).await.unwrap());
+    notification_service.close_sessions(user_id, &[session_id]).await;
+    assert!(wait_for_text(&mut framed, "SESSION_REVOKED").await, "revoking the session should close its socket");
+
+    // Its still unexpired token can't open a new one
+    let (_resp, mut framed) = awc::Client::new()
+        .ws(format!("{}?token={}", ws_url, token))
+        .connect()
+        .await
+        .expect("ws connect");
+    assert!(wait_for_text(&mut framed, "SESSION_REVOKED").await, "a revoked session's token should be refused");
+
+    // ...and is never acknowledged first
+    let (_resp, mut framed) = awc::Client::new()
+        .ws(format!("{}?token={}", ws_url, token))
+        .connect()
+        .await
+        .expect("ws connect");
+    assert!(!wait_for_text(&mut framed, "\"type\":\"authenticated\"").await, "a revoked session's token should not be acknowledged");
+}
+
+#[actix_web::test]
+async fn test_notifications_websocket_rejects_invalid_handshake_token() {
+    let pool = setup_test_db().await;
+    let ws_url = start_ws_server(NotificationService::new(pool));
+
+    let result = awc::Client::new()
+        .ws(format!("{}?token=not-a-token", ws_url))
+        .connect()
+        .await;
+
+    match result {
+        Err(awc::error::WsClientError::InvalidResponseStatus(status)) => {
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
         }
+        other => panic!("expected 401 handshake rejection, got {:?}", other.map(|_| ())),
     }
-    assert!(got_notification, "should receive notification via WS");
+}
+
+#[actix_web::test]
+async fn test_notifications_websocket_rejects_bare_user_id() {
+    use awc::ws;
+    use futures_util::SinkExt;
+
+    let pool = setup_test_db().await;
+    let ws_url = start_ws_server(NotificationService::new(pool.clone()));
+    let (user_id, _token) = create_ws_user(&pool).await;
+
+    let (_resp, mut framed) = awc::Client::new()
+        .ws(ws_url)
+        .connect()
+        .await
+        .expect("ws connect");
+
+    // The old unauthenticated handshake must no longer subscribe to a feed
+    let legacy_msg = json!({ "user_id": user_id }).to_string();
+    framed.send(ws::Message::Text(legacy_msg.into())).await.expect("send legacy auth");
+
+    assert!(wait_for_text(&mut framed, "AUTH_REQUIRED").await, "should receive AUTH_REQUIRED error frame");
 }
 
 #[actix_web::test]
 async fn test_notifications_flow_get_list_count_mark_read() {
     let pool = setup_test_db().await;
+    let notification_service = NotificationService::new(pool.clone());
     let app = test::init_service(create_app(pool)).await;
     // register and get token
     let email = format!("stats_user_{}@example.com", Uuid::new_v4());
@@ -183,19 +307,18 @@ async fn test_notifications_flow_get_list_count_mark_read() {
     let reg_resp = test::call_service(&app, reg_req).await;
     assert_eq!(reg_resp.status(), StatusCode::CREATED);
     #[derive(Debug, Deserialize)]
-    struct RegData2 { token: String }
+    struct RegUser2 { id: Uuid }
+    #[derive(Debug, Deserialize)]
+    struct RegData2 { user: RegUser2, token: String }
     let reg_body_json: ApiResponse<RegData2> = test::read_body_json(reg_resp).await;
-    let token = reg_body_json.data.unwrap().token;
+    let reg_data = reg_body_json.data.unwrap();
+    let token = reg_data.token;
 
-    // Crear una task, lo que debería disparar una notificación "assigned"
-    let payload = json!({ "title": "N1" });
-    let req_c = test::TestRequest::post()
-        .uri("/tasks")
-        .insert_header(("Authorization", format!("Bearer {}", token)))
-        .set_json(&payload)
-        .to_request();
-    let resp_c = test::call_service(&app, req_c).await;
-    assert_eq!(resp_c.status(), StatusCode::CREATED);
+    // Asignar una task dispara una notificación "assigned"
+    notification_service
+        .notify_task_assigned(reg_data.user.id, "N1", Uuid::new_v4())
+        .await
+        .expect("notify");
 
     // get_notifications
     let req_g = test::TestRequest::get()
@@ -228,3 +351,50 @@ async fn test_notifications_flow_get_list_count_mark_read() {
     let resp_m = test::call_service(&app, req_m).await;
     assert_eq!(resp_m.status(), StatusCode::OK);
 }
+
+#[actix_web::test]
+async fn test_notifications_cursor_pagination() {
+    let pool = setup_test_db().await;
+    let notification_service = NotificationService::new(pool.clone());
+    let app = test::init_service(create_app(pool.clone())).await;
+    let (user_id, token) = create_ws_user(&pool).await;
+
+    for i in 0..5 {
+        notification_service
+            .notify_task_assigned(user_id, &format!("Paged {}", i), Uuid::new_v4())
+            .await
+            .expect("notify");
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Page { notifications: Vec<NotificationResponse>, next_cursor: Option<String> }
+
+    let mut seen = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let req = test::TestRequest::get()
+            .uri(&format!("/notifications?limit=2&cursor={}", cursor))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: ApiResponse<Page> = test::read_body_json(resp).await;
+        let page = body.data.unwrap();
+        seen.extend(page.notifications.into_iter().map(|n| n.id));
+        match page.next_cursor {
+            Some(next) => cursor = next,
+            None => break,
+        }
+    }
+
+    // Newest first, each notification exactly once
+    assert_eq!(seen.len(), 5);
+    assert!(seen.windows(2).all(|w| w[0] > w[1]));
+
+    let req = test::TestRequest::get()
+        .uri("/notifications?cursor=garbage")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
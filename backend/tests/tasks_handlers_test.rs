@@ -2,11 +2,14 @@ use actix_web::{test, web, App, http::StatusCode};
 use serde::Deserialize;
 use serde_json::json;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
 use taskmaster_backend::handlers::{auth, tasks};
 use taskmaster_backend::models::Task;
 use taskmaster_backend::services::notification_service::NotificationService;
+use taskmaster_backend::services::storage::{LocalStorage, StorageBackend};
+use taskmaster_backend::services::mailer::{LogMailer, Mailer};
 
 // Mirror ApiResponse used by backend
 #[derive(Debug, Deserialize)]
@@ -28,9 +31,13 @@ async fn setup_test_db() -> PgPool {
 
 fn create_app(pool: PgPool) -> App<impl actix_web::dev::ServiceFactory<actix_web::dev::ServiceRequest, Config = (), Response = actix_web::dev::ServiceResponse, Error = actix_web::Error, InitError = ()>> {
     let notification_service = NotificationService::new(pool.clone());
+    let storage: Arc<dyn StorageBackend> = Arc::new(LocalStorage::new(std::env::temp_dir().join("taskmaster_test_attachments")));
+    let mailer: Arc<dyn Mailer> = Arc::new(LogMailer);
     App::new()
         .app_data(web::Data::new(pool))
         .app_data(web::Data::new(notification_service))
+        .app_data(web::Data::from(mailer))
+        .app_data(web::Data::from(storage))
         // auth routes for registering user and getting token
         .service(auth::register)
         // mount task routes under /tasks
@@ -0,0 +1,32 @@
+use taskmaster_backend::utils::byte_range::{parse_range, ByteRange, RangeRequest};
+
+fn partial(start: u64, end: u64) -> RangeRequest {
+    RangeRequest::Partial(ByteRange { start, end })
+}
+
+#[test]
+fn test_parse_single_ranges() {
+    assert_eq!(parse_range("bytes=0-9", 100), partial(0, 9));
+    assert_eq!(parse_range("bytes=90-", 100), partial(90, 99));
+    assert_eq!(parse_range("bytes=-10", 100), partial(90, 99));
+    // Ends past the file are clamped, suffixes longer than it take all of it
+    assert_eq!(parse_range("bytes=50-500", 100), partial(50, 99));
+    assert_eq!(parse_range("bytes=-500", 100), partial(0, 99));
+    assert_eq!(ByteRange { start: 10, end: 19 }.length(), 10);
+}
+
+#[test]
+fn test_parse_unsatisfiable_ranges() {
+    assert_eq!(parse_range("bytes=100-", 100), RangeRequest::Unsatisfiable);
+    assert_eq!(parse_range("bytes=150-200", 100), RangeRequest::Unsatisfiable);
+    assert_eq!(parse_range("bytes=-0", 100), RangeRequest::Unsatisfiable);
+    assert_eq!(parse_range("bytes=0-", 0), RangeRequest::Unsatisfiable);
+}
+
+#[test]
+fn test_parse_ignored_ranges_serve_whole_file() {
+    assert_eq!(parse_range("items=0-9", 100), RangeRequest::Full);
+    assert_eq!(parse_range("bytes=0-9,20-29", 100), RangeRequest::Full);
+    assert_eq!(parse_range("bytes=9-0", 100), RangeRequest::Full);
+    assert_eq!(parse_range("bytes=a-b", 100), RangeRequest::Full);
+}
@@ -0,0 +1,45 @@
+use taskmaster_backend::utils::content_type::{resolve, sniff};
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+#[test]
+fn test_sniff_known_signatures() {
+    assert_eq!(sniff(PNG), "image/png");
+    assert_eq!(sniff(b"\xff\xd8\xff\xe0\0\x10JFIF"), "image/jpeg");
+    assert_eq!(sniff(b"%PDF-1.7\n"), "application/pdf");
+    assert_eq!(sniff(b"RIFF\x24\0\0\0WEBPVP8 "), "image/webp");
+    assert_eq!(sniff(b"\x7fELF\x02\x01\x01"), "application/x-executable");
+    assert_eq!(sniff("plain notes, ñandú".as_bytes()), "text/plain");
+    assert_eq!(sniff(b"\x00\x01\x02\x03"), "application/octet-stream");
+
+    // Text starting with "MZ" isn't mistaken for a Windows executable
+    assert_eq!(sniff(b"MZ notes"), "text/plain");
+    let mut exe = vec![0u8; 0x80];
+    exe[..2].copy_from_slice(b"MZ");
+    exe[0x3c] = 0x40;
+    exe[0x40..0x44].copy_from_slice(b"PE\0\0");
+    assert_eq!(sniff(&exe), "application/x-msdownload");
+}
+
+#[test]
+fn test_resolve_declared_type() {
+    assert_eq!(resolve(PNG, Some("image/png")).unwrap(), "image/png");
+    assert_eq!(resolve(PNG, None).unwrap(), "image/png");
+    // Generic or unrelated claims give way to the content
+    assert_eq!(resolve(PNG, Some("application/octet-stream")).unwrap(), "image/png");
+    assert_eq!(resolve(PNG, Some("text/plain")).unwrap(), "image/png");
+    assert_eq!(resolve(b"a,b\n1,2\n", Some("text/csv; charset=utf-8")).unwrap(), "text/csv");
+    assert_eq!(resolve(b"{}", Some("application/json")).unwrap(), "application/json");
+    assert_eq!(
+        resolve(b"PK\x03\x04rest", Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")).unwrap(),
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+    );
+}
+
+#[test]
+fn test_resolve_rejects_spoofed_and_executable_files() {
+    assert!(resolve(b"<svg onload=alert(1)>", Some("image/png")).is_err());
+    assert!(resolve(b"just text", Some("application/pdf")).is_err());
+    assert!(resolve(b"\x7fELF\x02\x01\x01", Some("text/plain")).is_err());
+    assert!(resolve(b"\x7fELF\x02\x01\x01", None).is_err());
+}
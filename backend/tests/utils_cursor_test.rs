@@ -0,0 +1,29 @@
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use taskmaster_backend::utils::cursor;
+
+#[test]
+fn test_position_round_trip() {
+    assert!(cursor::position::<Uuid>(None).unwrap().is_none());
+    assert!(cursor::position::<Uuid>(Some("  ")).unwrap().is_none());
+    assert!(cursor::position::<Uuid>(Some("not-a-cursor")).is_err());
+
+    let at = Utc.with_ymd_and_hms(2026, 10, 1, 12, 0, 0).unwrap();
+    let mut rows = vec![(at, 3), (at, 2), (at, 1)];
+    let next = cursor::next_page(&mut rows, 2, |last| Some((last.0, last.1))).unwrap();
+    assert_eq!(rows, vec![(at, 3), (at, 2)]);
+
+    let position = cursor::position::<i32>(Some(&next)).unwrap().unwrap();
+    assert_eq!(position.at, at);
+    assert_eq!(position.id, 2);
+    // A cursor for one id type doesn't decode as another
+    assert!(cursor::position::<Uuid>(Some(&next)).is_err());
+}
+
+#[test]
+fn test_next_page_on_last_page() {
+    let mut rows = vec![1, 2];
+    assert_eq!(cursor::next_page(&mut rows, 2, |_| Some((Utc::now(), 0))), None);
+    assert_eq!(rows, vec![1, 2]);
+}
@@ -0,0 +1,29 @@
+use actix_web::test::TestRequest;
+
+use taskmaster_backend::utils::etag::{entity_tag, if_match, if_none_match};
+
+#[test]
+fn test_if_match() {
+    let req = |value: &str| TestRequest::default().insert_header(("If-Match", value)).to_http_request();
+
+    assert!(if_match(&TestRequest::default().to_http_request(), 3), "no header, no precondition");
+    assert!(if_match(&req("\"3\""), 3));
+    assert!(if_match(&req("\"1\", \"3\""), 3));
+    assert!(if_match(&req("*"), 3));
+    assert!(!if_match(&req("\"2\""), 3));
+    // If-Match compares strongly, so weak tags never match
+    assert!(!if_match(&req("W/\"3\""), 3));
+    assert!(!if_match(&req("3"), 3), "malformed tags fail the precondition");
+}
+
+#[test]
+fn test_if_none_match() {
+    let req = |value: &str| TestRequest::default().insert_header(("If-None-Match", value)).to_http_request();
+
+    assert!(!if_none_match(&TestRequest::default().to_http_request(), 3));
+    assert!(if_none_match(&req("\"3\""), 3));
+    assert!(if_none_match(&req("W/\"3\""), 3));
+    assert!(if_none_match(&req("*"), 3));
+    assert!(!if_none_match(&req("\"2\", \"4\""), 3));
+    assert_eq!(entity_tag(3).to_string(), "\"3\"");
+}
@@ -8,8 +8,8 @@ fn setup_test_env() {
     if std::env::var("JWT_SECRET").is_err() {
         std::env::set_var("JWT_SECRET", "test_secret_key_for_testing_12345");
     }
-    if std::env::var("JWT_EXPIRATION_HOURS").is_err() {
-        std::env::set_var("JWT_EXPIRATION_HOURS", "24");
+    if std::env::var("JWT_EXPIRATION_MINUTES").is_err() {
+        std::env::set_var("JWT_EXPIRATION_MINUTES", "15");
     }
 }
 
@@ -22,13 +22,15 @@ fn test_create_and_verify_token_ok() -> Result<()> {
 
     let email = "utils.tester@example.com";
     let user_id = Uuid::new_v4();
+    let session_id = Uuid::new_v4();
 
-    let token = create_token(email, &user_id)?;
+    let token = create_token(email, &user_id, &session_id)?;
     assert!(!token.is_empty(), "token should not be empty");
 
     let claims = verify_token(&token)?;
     assert_eq!(claims.sub, email);
     assert_eq!(claims.user_id, user_id.to_string());
+    assert_eq!(claims.sid, session_id.to_string());
     assert!(claims.exp > Utc::now().timestamp(), "exp should be in the future");
     assert!(claims.iat <= claims.exp, "iat should be <= exp");
 
@@ -0,0 +1,30 @@
+use taskmaster_backend::utils::mentions::{excerpt, extract_mentions};
+
+fn handles(text: &str) -> Vec<String> {
+    extract_mentions(text).into_iter().map(|m| m.handle).collect()
+}
+
+#[test]
+fn test_extract_mentions() {
+    assert_eq!(handles("@maria can you check with @Jon.Doe?"), vec!["maria", "jon.doe"]);
+    assert_eq!(handles("Thanks @maria. Also @maria again"), vec!["maria"]);
+    assert_eq!(handles("(@ana-b) and @josé_1"), vec!["ana-b", "josé_1"]);
+}
+
+#[test]
+fn test_extract_mentions_ignores_emails_and_bare_at() {
+    assert!(handles("write to maria@example.com").is_empty());
+    assert!(handles("meet @ 5pm, or @.").is_empty());
+}
+
+#[test]
+fn test_excerpt_around_mention() {
+    assert_eq!(excerpt("Hey @maria, see this", 4), "Hey @maria, see this");
+
+    let text = format!("{} @maria {}", "a".repeat(50), "b".repeat(100));
+    let mention = extract_mentions(&text).remove(0);
+    let excerpt = excerpt(&text, mention.position);
+    assert!(excerpt.starts_with('…') && excerpt.ends_with('…'));
+    assert!(excerpt.contains("@maria"));
+    assert_eq!(excerpt.chars().count(), 122);
+}
@@ -0,0 +1,85 @@
+use chrono::{TimeZone, Utc, Weekday};
+use taskmaster_backend::utils::recurrence::{Frequency, RecurrenceRule};
+
+#[test]
+fn test_parse_full_rule() {
+    let rule = RecurrenceRule::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=WE,MO;COUNT=5").unwrap();
+    assert_eq!(rule.frequency, Frequency::Weekly);
+    assert_eq!(rule.interval, 2);
+    assert_eq!(rule.by_weekday, vec![Weekday::Mon, Weekday::Wed]);
+    assert_eq!(rule.count, Some(5));
+    assert_eq!(rule.to_string(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;COUNT=5");
+}
+
+#[test]
+fn test_parse_until_date() {
+    let rule = RecurrenceRule::parse("FREQ=DAILY;UNTIL=20261231").unwrap();
+    assert_eq!(rule.until, Some(Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 59).unwrap()));
+}
+
+#[test]
+fn test_parse_rejects_invalid_rules() {
+    assert!(RecurrenceRule::parse("INTERVAL=2").is_err());
+    assert!(RecurrenceRule::parse("FREQ=HOURLY").is_err());
+    assert!(RecurrenceRule::parse("FREQ=DAILY;INTERVAL=0").is_err());
+    assert!(RecurrenceRule::parse("FREQ=DAILY;COUNT=3;UNTIL=20261231").is_err());
+    assert!(RecurrenceRule::parse("FREQ=MONTHLY;BYDAY=MO").is_err());
+    assert!(RecurrenceRule::parse("FREQ=WEEKLY;BYDAY=1MO").is_err());
+    assert!(RecurrenceRule::parse("FREQ=DAILY;BYMONTHDAY=3").is_err());
+}
+
+#[test]
+fn test_step_weekly_by_weekday_with_interval() {
+    let rule = RecurrenceRule::parse("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE").unwrap();
+    // Monday 2026-10-05 -> Wednesday same week -> Monday two weeks later
+    let monday = Utc.with_ymd_and_hms(2026, 10, 5, 9, 0, 0).unwrap();
+    let wednesday = rule.step(monday).unwrap();
+    assert_eq!(wednesday, Utc.with_ymd_and_hms(2026, 10, 7, 9, 0, 0).unwrap());
+    assert_eq!(rule.step(wednesday).unwrap(), Utc.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap());
+}
+
+#[test]
+fn test_step_daily_by_weekday_with_weekly_interval() {
+    let rule = RecurrenceRule::parse("FREQ=DAILY;INTERVAL=7;BYDAY=MO").unwrap();
+    // Tuesday 2026-10-06 -> next Monday -> a week after that
+    let tuesday = Utc.with_ymd_and_hms(2026, 10, 6, 9, 0, 0).unwrap();
+    let monday = rule.step(tuesday).unwrap();
+    assert_eq!(monday, Utc.with_ymd_and_hms(2026, 10, 12, 9, 0, 0).unwrap());
+    assert_eq!(rule.step(monday).unwrap(), Utc.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap());
+}
+
+#[test]
+fn test_step_monthly_skips_short_months() {
+    let rule = RecurrenceRule::parse("FREQ=MONTHLY").unwrap();
+    let jan_31 = Utc.with_ymd_and_hms(2027, 1, 31, 8, 0, 0).unwrap();
+    assert_eq!(rule.step(jan_31).unwrap(), Utc.with_ymd_and_hms(2027, 3, 31, 8, 0, 0).unwrap());
+}
+
+#[test]
+fn test_step_yearly_leap_day() {
+    let rule = RecurrenceRule::parse("FREQ=YEARLY").unwrap();
+    let leap_day = Utc.with_ymd_and_hms(2028, 2, 29, 0, 0, 0).unwrap();
+    assert_eq!(rule.step(leap_day).unwrap(), Utc.with_ymd_and_hms(2032, 2, 29, 0, 0, 0).unwrap());
+}
+
+#[test]
+fn test_next_occurrence_catches_up_and_respects_count() {
+    let rule = RecurrenceRule::parse("FREQ=DAILY;COUNT=10").unwrap();
+    let start = Utc.with_ymd_and_hms(2026, 10, 1, 9, 0, 0).unwrap();
+    let now = Utc.with_ymd_and_hms(2026, 10, 4, 12, 0, 0).unwrap();
+
+    // Occurrences that already passed still count towards COUNT
+    let (next, index) = rule.next_occurrence(start, 1, now).unwrap();
+    assert_eq!(next, Utc.with_ymd_and_hms(2026, 10, 5, 9, 0, 0).unwrap());
+    assert_eq!(index, 5);
+
+    assert!(rule.next_occurrence(start, 10, start).is_none());
+}
+
+#[test]
+fn test_next_occurrence_respects_until() {
+    let rule = RecurrenceRule::parse("FREQ=WEEKLY;UNTIL=20261010T000000Z").unwrap();
+    let start = Utc.with_ymd_and_hms(2026, 10, 1, 9, 0, 0).unwrap();
+    assert_eq!(rule.next_occurrence(start, 1, start).unwrap().1, 2);
+    assert!(rule.next_occurrence(start, 2, Utc.with_ymd_and_hms(2026, 10, 8, 9, 0, 0).unwrap()).is_none());
+}
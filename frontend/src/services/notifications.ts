@@ -16,10 +16,12 @@ export interface NotificationsResponse {
 }
 
 export interface WebSocketMessage {
-  type: 'notification' | 'notification_read' | 'ping' | 'pong' | 'authenticated';
+  type: 'notification' | 'notification_read' | 'ping' | 'pong' | 'authenticated' | 'error';
   notification?: Notification;
   notification_id?: number;
   status?: string;
+  code?: string;
+  message?: string;
 }
 
 class NotificationService {
@@ -76,8 +78,9 @@ class NotificationService {
       console.log('WebSocket connected');
       this.reconnectAttempts = 0;
       
-      // Authenticate with user ID
-      this.ws?.send(JSON.stringify({ user_id: userId }));
+      // Authenticate with the session token
+      const token = localStorage.getItem('token');
+      this.ws?.send(JSON.stringify({ type: 'auth', token }));
       
       // Notify status listeners
       this.statusListeners.forEach(listener => listener(true));
@@ -96,6 +99,9 @@ class NotificationService {
           case 'authenticated':
             console.log('WebSocket authenticated successfully');
             break;
+          case 'error':
+            console.error(`WebSocket closed by server: ${message.code} - ${message.message}`);
+            break;
           case 'ping':
             this.ws?.send(JSON.stringify({ type: 'pong' }));
             break;